@@ -0,0 +1,28 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_prev;
+
+layout(binding = 1) uniform Common {
+    float u_time;
+    vec2 u_resolution;
+};
+
+// A wandering dot leaving a fading, slowly rising trail in the previous frames
+void main() {
+    vec2 drift = vec2(0.0, 1.5) / u_resolution;
+    vec4 prev = texture(u_prev, v_uvs - drift);
+
+    float aspect = u_resolution.x / u_resolution.y;
+    vec2 st = vec2(v_uvs.x * aspect, v_uvs.y);
+    vec2 center = vec2(
+        0.5 * aspect + 0.35 * aspect * cos(u_time * 0.7),
+        0.5 + 0.3 * sin(u_time * 1.3)
+    );
+    float dot = smoothstep(0.03, 0.025, distance(st, center));
+    vec3 dot_color = 0.5 + 0.5 * cos(u_time + vec3(0.0, 2.0, 4.0));
+
+    color = vec4(max(prev.rgb * 0.985, dot_color * dot), 1.0);
+}
@@ -0,0 +1,27 @@
+#version 450
+precision mediump float;
+
+layout(location = 0) in vec2 v_uvs;
+layout(location = 1) in vec4 v_color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(set = 0, binding = 1) uniform Blur {
+    // Pixels between taps, along the direction of this pass
+    vec2 u_step;
+};
+
+layout(location = 0) out vec4 color;
+
+// One pass of a separable gaussian blur, used for the frosted glass text backdrop
+void main() {
+    vec2 texel = u_step / vec2(textureSize(u_texture, 0));
+    vec4 sum = vec4(0.0);
+    float total = 0.0;
+    for (int i = -6; i <= 6; i++) {
+        float weight = exp(-float(i * i) / 18.0);
+        sum += texture(u_texture, v_uvs + float(i) * texel) * weight;
+        total += weight;
+    }
+    color = sum / total * v_color;
+}
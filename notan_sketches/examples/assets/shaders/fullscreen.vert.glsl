@@ -0,0 +1,10 @@
+#version 450
+layout(location = 0) in vec2 a_pos;
+
+layout(location = 0) out vec2 v_uvs;
+
+// Full-screen quad for textured shader passes, with uvs from 0,0 at the bottom left corner
+void main() {
+    v_uvs = a_pos * 0.5 + 0.5;
+    gl_Position = vec4(a_pos, 0.0, 1.0);
+}
@@ -0,0 +1,21 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+layout(binding = 1) uniform sampler2D u_bloom;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// Adds the blurred bright parts back over the scene, u_params.x times as bright
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    vec3 bloom = texture(u_bloom, v_uvs).rgb;
+    color = vec4(scene.rgb + bloom * u_params.x, scene.a);
+}
@@ -0,0 +1,26 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// One pass of a separable gaussian blur, along u_params.xy, u_params.z pixels wide
+void main() {
+    vec2 texel = u_params.xy * (u_params.z / 8.0) / u_resolution;
+    vec4 sum = vec4(0.0);
+    float total = 0.0;
+    for (int i = -8; i <= 8; i++) {
+        float weight = exp(-float(i * i) / 32.0);
+        sum += texture(u_texture, v_uvs + float(i) * texel) * weight;
+        total += weight;
+    }
+    color = sum / total;
+}
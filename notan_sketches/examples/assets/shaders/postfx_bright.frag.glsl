@@ -0,0 +1,21 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// Keeps the parts brighter than the threshold in u_params.x, for bloom
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    float brightness = max(scene.r, max(scene.g, scene.b));
+    float glow = smoothstep(u_params.x, u_params.x + 0.1, brightness);
+    color = vec4(scene.rgb * glow, scene.a);
+}
@@ -0,0 +1,22 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// Splits red and blue apart towards the edges, by up to u_params.x pixels in the corners
+void main() {
+    vec2 offset = (v_uvs - 0.5) * 2.0 * u_params.x / u_resolution;
+    vec4 scene = texture(u_texture, v_uvs);
+    float red = texture(u_texture, v_uvs + offset).r;
+    float blue = texture(u_texture, v_uvs - offset).b;
+    color = vec4(red, scene.g, blue, scene.a);
+}
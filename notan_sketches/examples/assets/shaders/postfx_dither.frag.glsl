@@ -0,0 +1,29 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+const float BAYER[16] = float[16](
+    0.0, 8.0, 2.0, 10.0,
+    12.0, 4.0, 14.0, 6.0,
+    3.0, 11.0, 1.0, 9.0,
+    15.0, 7.0, 13.0, 5.0
+);
+
+// Reduces each channel to u_params.x levels with an ordered 4x4 dither
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    ivec2 cell = ivec2(mod(gl_FragCoord.xy, 4.0));
+    float threshold = (BAYER[cell.y * 4 + cell.x] + 0.5) / 16.0 - 0.5;
+    float steps = max(u_params.x - 1.0, 1.0);
+    color = vec4(floor(scene.rgb * steps + threshold + 0.5) / steps, scene.a);
+}
@@ -0,0 +1,33 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+layout(binding = 1) uniform sampler2D u_lut;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// Looks a color up in a strip of u_params.x slices of u_params.x by u_params.x texels, with
+// red across each slice, green down it, and blue from slice to slice
+vec3 lookup(vec3 rgb, float size) {
+    float blue = rgb.b * (size - 1.0);
+    float slice = floor(blue);
+    float next_slice = min(slice + 1.0, size - 1.0);
+    vec2 texel = rgb.rg * (size - 1.0) + 0.5;
+    vec2 uv = vec2((slice * size + texel.x) / (size * size), texel.y / size);
+    vec2 next_uv = vec2((next_slice * size + texel.x) / (size * size), texel.y / size);
+    return mix(texture(u_lut, uv).rgb, texture(u_lut, next_uv).rgb, blue - slice);
+}
+
+// Grades colors with the LUT, mixed in by u_params.y
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    vec3 graded = lookup(clamp(scene.rgb, 0.0, 1.0), u_params.x);
+    color = vec4(mix(scene.rgb, graded, u_params.y), scene.a);
+}
@@ -0,0 +1,26 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+float hash(vec2 p) {
+    p = fract(p * vec2(443.897, 441.423));
+    p += dot(p, p.yx + 19.19);
+    return fract((p.x + p.y) * p.x);
+}
+
+// Adds noise of up to u_params.x, changing every frame like film grain
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    float noise = hash(v_uvs * u_resolution + fract(u_time) * 1000.0) - 0.5;
+    color = vec4(scene.rgb + noise * u_params.x, scene.a);
+}
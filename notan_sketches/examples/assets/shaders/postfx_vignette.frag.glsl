@@ -0,0 +1,22 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_texture;
+
+layout(binding = 2) uniform PostFx {
+    // Parameters of the effect, see notan_sketches::postfx::PostFx
+    vec4 u_params;
+    vec2 u_resolution;
+    float u_time;
+};
+
+// Darkens by up to u_params.x towards the corners, from u_params.y of the way out
+void main() {
+    vec4 scene = texture(u_texture, v_uvs);
+    // 0 at the center, 1 at the corners
+    float distance_out = length(v_uvs - 0.5) * sqrt(2.0);
+    float shade = 1.0 - u_params.x * smoothstep(u_params.y, 1.0, distance_out);
+    color = vec4(scene.rgb * shade, scene.a);
+}
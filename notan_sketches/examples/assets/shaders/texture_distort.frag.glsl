@@ -0,0 +1,25 @@
+#version 450
+precision mediump float;
+layout(location = 0) in vec2 v_uvs;
+layout(location = 0) out vec4 color;
+
+layout(binding = 0) uniform sampler2D u_tile;
+layout(binding = 1) uniform sampler2D u_brush;
+
+layout(binding = 2) uniform Common {
+    float u_time;
+    vec2 u_resolution;
+};
+
+// Ripples the tile image, cut out by the shape of a brush stroke
+void main() {
+    // Images are stored top row first
+    vec2 st = vec2(v_uvs.x, 1.0 - v_uvs.y);
+    vec2 ripple = vec2(
+        sin(st.y * 14.0 + u_time * 1.3),
+        cos(st.x * 11.0 + u_time * 0.9)
+    ) * 0.012;
+    vec4 tile = texture(u_tile, st * 2.0 + ripple);
+    float mask = texture(u_brush, st).a;
+    color = vec4(tile.rgb, tile.a * mask);
+}
@@ -10,6 +10,7 @@ use notan_sketches::colors::PalettesSelection;
 use notan_sketches::gridutils::Grid;
 use notan_sketches::shaderutils::{
     create_hot_shape_pipeline, CommonData, ShaderReloadManager, ShaderRenderTexture,
+    SHAPES_VERT_PATH,
 };
 use notan_sketches::utils::{
     get_common_win_config, get_draw_setup, get_rng, get_work_size_for_screen, ScreenDimensions,
@@ -167,8 +168,12 @@ fn init(app: &mut App, gfx: &mut Graphics) -> State {
     #[cfg(not(debug_assertions))]
     let pipeline = create_shape_pipeline(gfx, Some(&FRAG)).unwrap();
     #[cfg(debug_assertions)]
-    let pipeline =
-        create_hot_shape_pipeline(gfx, "examples/assets/shaders/tile_blend.frag.glsl").unwrap();
+    let pipeline = create_hot_shape_pipeline(
+        gfx,
+        SHAPES_VERT_PATH,
+        "examples/assets/shaders/tile_blend.frag.glsl",
+    )
+    .unwrap();
 
     let common_data = CommonData::new(0.0, work_size);
     let common_ubo = gfx
@@ -219,7 +224,10 @@ fn init(app: &mut App, gfx: &mut Graphics) -> State {
         srt,
         tile_colors_dirty: false,
         #[cfg(debug_assertions)]
-        hot_mgr: ShaderReloadManager::default(),
+        hot_mgr: ShaderReloadManager::new(&[
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/tile_blend.frag.glsl",
+        ]),
     }
 }
 
@@ -292,7 +300,11 @@ fn main() -> Result<(), String> {
 fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
     #[cfg(debug_assertions)]
     if state.hot_mgr.needs_reload() {
-        match create_hot_shape_pipeline(gfx, "examples/assets/shaders/tile_blend.frag.glsl") {
+        match create_hot_shape_pipeline(
+            gfx,
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/tile_blend.frag.glsl",
+        ) {
             Ok(pipeline) => state.pipeline = pipeline,
             Err(err) => log::error!("{}", err),
         }
@@ -315,15 +327,17 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
     let u_time = app.timer.elapsed_f32();
     let common_data = CommonData::new(u_time, state.work_size);
 
-    state.srt.draw_filled(
+    if let Err(err) = state.srt.draw_filled(
         gfx,
         &state.pipeline,
-        vec![
+        &[
             &state.common_ubo,
             &state.tile_colors_ubo,
             &state.tile_grid_info_ubo,
         ],
-    );
+    ) {
+        log::error!("{}", err);
+    }
 
     let mut draw = get_draw_setup(gfx, state.work_size, false, Color::WHITE);
 
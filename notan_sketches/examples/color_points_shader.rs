@@ -5,6 +5,7 @@ use notan::prelude::*;
 use notan_sketches::colors;
 use notan_sketches::shaderutils::{
     create_hot_shape_pipeline, CommonData, ShaderReloadManager, ShaderRenderTexture,
+    SHAPES_VERT_PATH,
 };
 use notan_sketches::utils::{
     get_common_win_config, get_draw_setup, get_rng, set_html_bgcolor, ScreenDimensions,
@@ -77,8 +78,12 @@ fn prep_ubos(
 }
 
 fn init(gfx: &mut Graphics) -> State {
-    let pipeline =
-        create_hot_shape_pipeline(gfx, "examples/assets/shaders/color_points.frag.glsl").unwrap();
+    let pipeline = create_hot_shape_pipeline(
+        gfx,
+        SHAPES_VERT_PATH,
+        "examples/assets/shaders/color_points.frag.glsl",
+    )
+    .unwrap();
     let common_data = CommonData::new(0.0, WORK_SIZE);
     let color1_uniform = ColorSourceUniform {
         color: Vec3::new(COLOR1.r, COLOR1.g, COLOR1.b),
@@ -120,7 +125,10 @@ fn init(gfx: &mut Graphics) -> State {
         color1,
         color2,
         srt,
-        hot_mgr: ShaderReloadManager::default(),
+        hot_mgr: ShaderReloadManager::new(&[
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/color_points.frag.glsl",
+        ]),
         rng: rng,
     }
 }
@@ -147,7 +155,11 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
     let common_data = CommonData::new(u_time, WORK_SIZE);
 
     if state.hot_mgr.needs_reload() {
-        match create_hot_shape_pipeline(gfx, "examples/assets/shaders/color_points.frag.glsl") {
+        match create_hot_shape_pipeline(
+            gfx,
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/color_points.frag.glsl",
+        ) {
             Ok(pipeline) => state.pipeline = pipeline,
             Err(err) => log::error!("{}", err),
         }
@@ -166,16 +178,18 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
         );
     }
 
-    state.srt.draw_filled(
+    if let Err(err) = state.srt.draw_filled(
         gfx,
         &state.pipeline,
-        vec![
+        &[
             &state.common_ubo,
             &state.bg_color_ubo,
             &state.color1.ubo,
             &state.color2.ubo,
         ],
-    );
+    ) {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.srt.rt)
         .position(0.0, 0.0)
@@ -0,0 +1,127 @@
+use notan::draw::*;
+use notan::log;
+use notan::math::Vec2;
+use notan::prelude::*;
+#[cfg(debug_assertions)]
+use notan_sketches::shaderutils::{
+    create_hot_texture_pipeline, ShaderReloadManager, FULLSCREEN_VERT_PATH,
+};
+use notan_sketches::shaderutils::{CommonData, FeedbackRenderTexture, PREV_FRAME_TEXTURE};
+use notan_sketches::utils::{
+    get_common_win_config, get_draw_setup, set_html_bgcolor, ScreenDimensions,
+};
+
+// const WORK_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+const WORK_SIZE: Vec2 = ScreenDimensions::RES_1080P;
+#[cfg(debug_assertions)]
+const FRAG_PATH: &str = "examples/assets/shaders/feedback_trails.frag.glsl";
+
+#[cfg(not(debug_assertions))]
+const FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/feedback_trails.frag.glsl");
+
+#[derive(AppState)]
+struct State {
+    pub pipeline: Pipeline,
+    pub common_ubo: Buffer,
+    pub feedback: FeedbackRenderTexture,
+    #[cfg(debug_assertions)]
+    pub hot_mgr: ShaderReloadManager,
+}
+
+fn init(gfx: &mut Graphics) -> State {
+    #[cfg(not(debug_assertions))]
+    let pipeline =
+        notan_sketches::shaderutils::create_texture_pipeline(gfx, &FRAG, &[PREV_FRAME_TEXTURE])
+            .unwrap();
+    #[cfg(debug_assertions)]
+    let pipeline =
+        create_hot_texture_pipeline(gfx, FULLSCREEN_VERT_PATH, FRAG_PATH, &[PREV_FRAME_TEXTURE])
+            .unwrap();
+
+    let common_ubo = gfx
+        .create_uniform_buffer(1, "Common")
+        .with_data(&CommonData::new(0.0, WORK_SIZE))
+        .build()
+        .unwrap();
+
+    // Trails start from a black frame
+    let mut feedback = FeedbackRenderTexture::new(gfx, WORK_SIZE.x, WORK_SIZE.y);
+    feedback.seed(gfx, |seed_draw| {
+        seed_draw
+            .rect((0.0, 0.0), (seed_draw.width(), seed_draw.height()))
+            .fill_color(Color::BLACK)
+            .fill();
+    });
+
+    State {
+        pipeline,
+        common_ubo,
+        feedback,
+        #[cfg(debug_assertions)]
+        hot_mgr: ShaderReloadManager::new(&[FULLSCREEN_VERT_PATH, FRAG_PATH]),
+    }
+}
+
+fn update(state: &mut State) {
+    #[cfg(debug_assertions)]
+    state.hot_mgr.update();
+}
+
+fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
+    let draw = &mut get_draw_setup(gfx, WORK_SIZE, false, Color::BLACK);
+
+    #[cfg(debug_assertions)]
+    if state.hot_mgr.needs_reload() {
+        match create_hot_texture_pipeline(
+            gfx,
+            FULLSCREEN_VERT_PATH,
+            FRAG_PATH,
+            &[PREV_FRAME_TEXTURE],
+        ) {
+            Ok(pipeline) => state.pipeline = pipeline,
+            Err(err) => log::error!("{}", err),
+        }
+    }
+
+    let common_data = CommonData::new(app.timer.elapsed_f32(), WORK_SIZE);
+    gfx.set_buffer_data(&state.common_ubo, &common_data);
+
+    if let Err(err) = state
+        .feedback
+        .draw(gfx, &state.pipeline, &[&state.common_ubo], &[])
+    {
+        log::error!("{}", err);
+    }
+
+    draw.image(state.feedback.texture())
+        .position(0.0, 0.0)
+        .size(WORK_SIZE.x, WORK_SIZE.y);
+
+    gfx.render(draw);
+}
+
+#[notan_main]
+fn main() -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let win_config = get_common_win_config()
+        .set_high_dpi(true)
+        .set_vsync(true)
+        .set_size(
+            ScreenDimensions::RES_1080P.x as u32,
+            ScreenDimensions::RES_1080P.y as u32,
+        );
+
+    set_html_bgcolor(Color::BLACK);
+
+    #[cfg(target_arch = "wasm32")]
+    let win_config = get_common_win_config().set_high_dpi(true);
+
+    notan::init_with(init)
+        .add_config(log::LogConfig::debug())
+        .add_config(win_config)
+        .add_config(DrawConfig) // Simple way to add the draw extension
+        .update(update)
+        .draw(draw)
+        .build()
+}
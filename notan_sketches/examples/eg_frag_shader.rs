@@ -138,11 +138,13 @@ fn init(gfx: &mut Graphics) -> State {
 fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
     let draw = &mut get_draw_setup(gfx, WORK_SIZE, false, Color::BLUE);
 
-    state.red_green_srt.draw_filled(
+    if let Err(err) = state.red_green_srt.draw_filled(
         gfx,
         &state.pipeline,
-        vec![&state.red_green_ubo, &state.common_ubo],
-    );
+        &[&state.red_green_ubo, &state.common_ubo],
+    ) {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.red_green_srt.rt)
         .position(50.0, 50.0)
@@ -157,41 +159,48 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
         .size(600.0, 100.0);
 
     // blue_green_srt with blue_green_ubo & common_ubo
-    state.blue_green_srt.draw_filled(
+    if let Err(err) = state.blue_green_srt.draw_filled(
         gfx,
         &state.pipeline,
-        vec![&state.blue_green_ubo, &state.common_ubo],
-    );
+        &[&state.blue_green_ubo, &state.common_ubo],
+    ) {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.blue_green_srt.rt)
         .position(1200.0, 50.0)
         .size(100.0, 100.0);
 
     // srt with red_green_ubo & common_ubo again
-    state.red_green_srt.draw_filled(
+    if let Err(err) = state.red_green_srt.draw_filled(
         gfx,
         &state.pipeline,
-        vec![&state.red_green_ubo, &state.common_ubo],
-    );
+        &[&state.red_green_ubo, &state.common_ubo],
+    ) {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.red_green_srt.rt)
         .position(1350.0, 50.0)
         .size(100.0, 100.0);
 
     // plot_srt with common_ubo2
-    state
+    if let Err(err) = state
         .plot_srt
-        .draw_filled(gfx, &state.pipeline2, vec![&state.common_ubo2]);
+        .draw_filled(gfx, &state.pipeline2, &[&state.common_ubo2])
+    {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.plot_srt.rt)
         .position(1500.0, 50.0)
         .size(100.0, 100.0);
 
     // boxes srt with red_green_ubo & common_ubo
-    state.shapes_srt.draw(
+    if let Err(err) = state.shapes_srt.draw(
         gfx,
         &state.pipeline,
-        vec![&state.red_green_ubo, &state.common_ubo],
+        &[&state.red_green_ubo, &state.common_ubo],
         |srtdraw| {
             srtdraw
                 .rect((0.0, 0.0), (srtdraw.width(), srtdraw.height()))
@@ -222,7 +231,9 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
                 .fill_color(Color::GRAY)
                 .fill();
         },
-    );
+    ) {
+        log::error!("{}", err);
+    }
 
     draw.image(&state.shapes_srt.rt)
         .position(50.0, 200.0)
@@ -0,0 +1,172 @@
+use notan::draw::*;
+use notan::log;
+use notan::math::Vec2;
+use notan::prelude::*;
+use notan_sketches::colors;
+use notan_sketches::postfx::{create_lut_texture, PostFx, PostFxChain};
+use notan_sketches::utils::{
+    get_common_win_config, get_draw_setup, get_rng, CapturingTexture, ScreenDimensions,
+};
+
+const WORK_SIZE: Vec2 = ScreenDimensions::RES_1080P;
+const BG_COLOR: Color = colors::STONE;
+const DOT_COLORS: [Color; 4] = [colors::BANANA, colors::SALMON, colors::AEGEAN, Color::WHITE];
+const KEYS: [KeyCode; 7] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+];
+
+#[derive(AppState)]
+struct State {
+    pub rng: Random,
+    pub capture: CapturingTexture,
+    pub postfx: PostFxChain,
+    /// Every effect, in the order they are applied, toggled by the number keys
+    pub effects: Vec<PostFx>,
+    pub enabled: [bool; 7],
+    pub capture_next_draw: bool,
+}
+
+fn init(gfx: &mut Graphics) -> State {
+    let (rng, seed) = get_rng(None);
+    log::info!("Seed: {}", seed);
+
+    let capture = CapturingTexture::new(
+        gfx,
+        &WORK_SIZE,
+        BG_COLOR,
+        format!("renders/eg_postfx/{}", seed),
+        0.0,
+    );
+
+    // Warm highlights and cool shadows
+    let lut = create_lut_texture(gfx, 16, |[r, g, b]| {
+        let luma = r * 0.3 + g * 0.59 + b * 0.11;
+        [r + luma * 0.1, g, b + (1.0 - luma) * 0.1]
+    })
+    .unwrap();
+
+    let effects = vec![
+        PostFx::ColorGrade {
+            lut,
+            lut_size: 16,
+            strength: 1.0,
+        },
+        PostFx::Bloom {
+            threshold: 0.75,
+            intensity: 0.8,
+            radius: 24.0,
+        },
+        PostFx::Blur { radius: 6.0 },
+        PostFx::ChromaticAberration { offset: 6.0 },
+        PostFx::Vignette {
+            strength: 0.6,
+            radius: 0.4,
+        },
+        PostFx::FilmGrain { amount: 0.08 },
+        PostFx::Dither { levels: 6.0 },
+    ];
+
+    State {
+        rng,
+        capture,
+        postfx: PostFxChain::new(gfx, WORK_SIZE.x, WORK_SIZE.y).unwrap(),
+        effects,
+        enabled: [true, true, false, true, true, true, false],
+        capture_next_draw: false,
+    }
+}
+
+fn update(app: &mut App, state: &mut State) {
+    for (index, key) in KEYS.iter().enumerate() {
+        if app.keyboard.was_pressed(*key) {
+            state.enabled[index] = !state.enabled[index];
+            log::info!(
+                "{}: {}",
+                state.effects[index].label(),
+                if state.enabled[index] { "on" } else { "off" }
+            );
+        }
+    }
+
+    if app.keyboard.was_pressed(KeyCode::KeyC) {
+        state.capture_next_draw = true;
+    }
+}
+
+fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
+    // Dots accumulate in the capture texture, which post-processing leaves as it is
+    let draw = &mut state.capture.render_texture.create_draw();
+    for _ in 0..3 {
+        let color = DOT_COLORS[state.rng.random_range(0..DOT_COLORS.len())];
+        draw.circle(state.rng.random_range(4.0..40.0))
+            .position(
+                state.rng.random_range(0.0..WORK_SIZE.x),
+                state.rng.random_range(0.0..WORK_SIZE.y),
+            )
+            .fill_color(color)
+            .alpha(0.6)
+            .fill();
+    }
+    gfx.render_to(&state.capture.render_texture, draw);
+
+    state.postfx.effects = state
+        .effects
+        .iter()
+        .zip(state.enabled)
+        .filter(|(_, enabled)| *enabled)
+        .map(|(effect, _)| effect.clone())
+        .collect();
+    let rdraw = &mut get_draw_setup(gfx, WORK_SIZE, true, BG_COLOR);
+    match state
+        .postfx
+        .apply(gfx, &state.capture.render_texture, app.timer.elapsed_f32())
+    {
+        Ok(processed) => {
+            if state.capture_next_draw {
+                state.capture.capture_texture(app, gfx, processed);
+                state.capture_next_draw = false;
+            }
+            rdraw
+                .image(processed)
+                .position(0.0, 0.0)
+                .size(WORK_SIZE.x, WORK_SIZE.y);
+        }
+        Err(err) => {
+            log::error!("{}", err);
+            rdraw
+                .image(&state.capture.render_texture)
+                .position(0.0, 0.0)
+                .size(WORK_SIZE.x, WORK_SIZE.y);
+        }
+    }
+    gfx.render(rdraw);
+}
+
+#[notan_main]
+fn main() -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let win_config = get_common_win_config()
+        .set_high_dpi(true)
+        .set_vsync(true)
+        .set_size(
+            ScreenDimensions::RES_1080P.x as u32,
+            ScreenDimensions::RES_1080P.y as u32,
+        );
+
+    #[cfg(target_arch = "wasm32")]
+    let win_config = get_common_win_config().set_high_dpi(true);
+
+    notan::init_with(init)
+        .add_config(log::LogConfig::debug())
+        .add_config(win_config)
+        .add_config(DrawConfig) // Simple way to add the draw extension
+        .update(update)
+        .draw(draw)
+        .build()
+}
@@ -0,0 +1,157 @@
+use notan::draw::*;
+use notan::log;
+use notan::math::Vec2;
+use notan::prelude::*;
+#[cfg(debug_assertions)]
+use notan_sketches::hotreload::read_asset;
+use notan_sketches::hotreload::HotAsset;
+#[cfg(debug_assertions)]
+use notan_sketches::shaderutils::{
+    create_hot_texture_pipeline, ShaderReloadManager, FULLSCREEN_VERT_PATH,
+};
+use notan_sketches::shaderutils::{CommonData, ShaderRenderTexture};
+use notan_sketches::utils::{
+    get_common_win_config, get_draw_setup, set_html_bgcolor, ScreenDimensions,
+};
+
+// const WORK_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+const WORK_SIZE: Vec2 = ScreenDimensions::RES_1080P;
+#[cfg(debug_assertions)]
+const FRAG_PATH: &str = "examples/assets/shaders/texture_distort.frag.glsl";
+#[cfg(debug_assertions)]
+const BRUSH_PATH: &str = "examples/assets/brushes/splat.png";
+/// Samplers of the shader, in the order their textures are given to `draw_textured()`
+const TEXTURE_NAMES: [&str; 2] = ["u_tile", "u_brush"];
+
+#[cfg(not(debug_assertions))]
+const FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/texture_distort.frag.glsl");
+
+#[derive(AppState)]
+struct State {
+    pub pipeline: Pipeline,
+    pub common_ubo: Buffer,
+    pub tile_texture: Texture,
+    /// Reloaded when the image changes in debug builds, and embedded in release builds
+    pub brush: HotAsset<Texture>,
+    pub srt: ShaderRenderTexture,
+    #[cfg(debug_assertions)]
+    pub hot_mgr: ShaderReloadManager,
+}
+
+fn init(gfx: &mut Graphics) -> State {
+    #[cfg(not(debug_assertions))]
+    let pipeline =
+        notan_sketches::shaderutils::create_texture_pipeline(gfx, &FRAG, &TEXTURE_NAMES).unwrap();
+    #[cfg(debug_assertions)]
+    let pipeline =
+        create_hot_texture_pipeline(gfx, FULLSCREEN_VERT_PATH, FRAG_PATH, &TEXTURE_NAMES).unwrap();
+
+    let common_ubo = gfx
+        .create_uniform_buffer(2, "Common")
+        .with_data(&CommonData::new(0.0, WORK_SIZE))
+        .build()
+        .unwrap();
+
+    // The tile repeats, as the shader samples it at twice its size
+    let tile_texture = gfx
+        .create_texture()
+        .from_image(include_bytes!("assets/tiles/tile3_4k.png"))
+        .with_wrap(TextureWrap::Repeat, TextureWrap::Repeat)
+        .build()
+        .unwrap();
+
+    #[cfg(not(debug_assertions))]
+    let brush = HotAsset::new(
+        create_brush_texture(gfx, include_bytes!("assets/brushes/splat.png")).unwrap(),
+        &[] as &[&str],
+    );
+    #[cfg(debug_assertions)]
+    let brush = HotAsset::load(&[BRUSH_PATH], || {
+        create_brush_texture(gfx, &read_asset(BRUSH_PATH)?)
+    })
+    .unwrap();
+
+    let srt = ShaderRenderTexture::new(gfx, WORK_SIZE.x, WORK_SIZE.y);
+
+    State {
+        pipeline,
+        common_ubo,
+        tile_texture,
+        brush,
+        srt,
+        #[cfg(debug_assertions)]
+        hot_mgr: ShaderReloadManager::new(&[FULLSCREEN_VERT_PATH, FRAG_PATH]),
+    }
+}
+
+fn create_brush_texture(gfx: &mut Graphics, image: &[u8]) -> Result<Texture, String> {
+    gfx.create_texture()
+        .from_image(image)
+        .with_filter(TextureFilter::Linear, TextureFilter::Linear)
+        .build()
+}
+
+fn update(state: &mut State) {
+    #[cfg(debug_assertions)]
+    state.hot_mgr.update();
+}
+
+fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
+    let draw = &mut get_draw_setup(gfx, WORK_SIZE, false, Color::BLACK);
+
+    #[cfg(debug_assertions)]
+    if state.hot_mgr.needs_reload() {
+        match create_hot_texture_pipeline(gfx, FULLSCREEN_VERT_PATH, FRAG_PATH, &TEXTURE_NAMES) {
+            Ok(pipeline) => state.pipeline = pipeline,
+            Err(err) => log::error!("{}", err),
+        }
+    }
+    #[cfg(debug_assertions)]
+    state
+        .brush
+        .update(|| create_brush_texture(gfx, &read_asset(BRUSH_PATH)?));
+
+    let common_data = CommonData::new(app.timer.elapsed_f32(), WORK_SIZE);
+    gfx.set_buffer_data(&state.common_ubo, &common_data);
+
+    if let Err(err) = state.srt.draw_textured(
+        gfx,
+        &state.pipeline,
+        &[&state.common_ubo],
+        &[&state.tile_texture, &state.brush.asset],
+    ) {
+        log::error!("{}", err);
+    }
+
+    draw.image(&state.srt.rt)
+        .position(0.0, 0.0)
+        .size(WORK_SIZE.x, WORK_SIZE.y);
+
+    gfx.render(draw);
+}
+
+#[notan_main]
+fn main() -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let win_config = get_common_win_config()
+        .set_high_dpi(true)
+        .set_vsync(true)
+        .set_size(
+            ScreenDimensions::RES_1080P.x as u32,
+            ScreenDimensions::RES_1080P.y as u32,
+        );
+
+    set_html_bgcolor(Color::BLACK);
+
+    #[cfg(target_arch = "wasm32")]
+    let win_config = get_common_win_config().set_high_dpi(true);
+
+    notan::init_with(init)
+        .add_config(log::LogConfig::debug())
+        .add_config(win_config)
+        .add_config(DrawConfig) // Simple way to add the draw extension
+        .update(update)
+        .draw(draw)
+        .build()
+}
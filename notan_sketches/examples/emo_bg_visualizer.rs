@@ -1,10 +1,13 @@
-use notan::app::Event;
+use notan::app::{DroppedFile, Event};
 use notan::draw::*;
 use notan::egui::{self, *};
 use notan::extra::FpsLimit;
 use notan::log;
 use notan::math::Vec2;
 use notan::prelude::*;
+use notan_sketches::emotion::analyzer::Lexicon;
+use notan_sketches::emotion::arc::EmotionArc;
+use notan_sketches::emotion::mapping::{ColorMappingRegistry, ColorMappingTable};
 use notan_sketches::emotion::*;
 use notan_sketches::utils::{
     get_common_win_config, get_draw_setup, scale_font, set_html_bgcolor, CommonHelpModal,
@@ -12,11 +15,27 @@ use notan_sketches::utils::{
 };
 use notan_touchy::{TouchGesture, TouchState};
 // use serde_json::{Result as JsonResult, Value};
+#[cfg(target_arch = "wasm32")]
+use notan_sketches::emotion_bg_visualizer::library::FileInput;
+use notan_sketches::emotion_bg_visualizer::library::{
+    is_color_mapping_file, parse_document, restore_color_mappings, save_color_mappings,
+    DocumentLibrary,
+};
+use notan_sketches::emotion_bg_visualizer::presentation::{Presentation, DEFAULT_PRESENTATION_WPM};
+use notan_sketches::emotion_bg_visualizer::read_along::{
+    read_along_lines, ReadAlongCursor, ReadAlongLine,
+};
+use notan_sketches::emotion_bg_visualizer::settings::{
+    ReadPosition, ReaderSettings, SETTINGS_VERSION,
+};
 use notan_sketches::emotion_bg_visualizer::visualizers::color_transition::ColorTransitionVisualizer;
 use notan_sketches::emotion_bg_visualizer::visualizers::tile::TilesVisualizer;
 use notan_sketches::emotion_bg_visualizer::visualizers::tiled_shaders::TiledShadersVisualizer;
-use notan_sketches::emotion_bg_visualizer::visualizers::VisualizerSelection;
+use notan_sketches::emotion_bg_visualizer::visualizers::{
+    LineHighlight, ParagraphLayout, VisualizerSelection,
+};
 use notan_sketches::emotion_bg_visualizer::{get_work_size, EmoVisualizerFull};
+use std::collections::HashMap;
 use FontFamily::{Monospace, Proportional};
 
 // See details at https://stackoverflow.com/a/42764117
@@ -31,6 +50,7 @@ const EMOCAT_DOCS: [&'static str; 7] = [
     include_str!("assets/lb_whitman01.json"),
     // include_str!("assets/the_stagger.json"),
 ];
+const DEFAULT_COLOR_MAPPING: &str = "Plutchik";
 const CLEAR_COLOR: Color = Color::WHITE;
 const TITLE_COLOR: Color = Color::BLACK;
 // const HELP_MODAL_TXTCOLOR: Color = Color::BLACK;
@@ -39,7 +59,9 @@ const HELP_MODAL_BGCOLOR: Color = Color::from_rgb(0.95, 0.95, 0.9);
 const ANALYSIS_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(128, 97, 0, 128);
 const READ_VIEW_GUI_COLOR: egui::Color32 =
     egui::Color32::from_rgba_premultiplied(128, 128, 128, 128);
-const DYNAMIC_TEXT_COLOR: bool = false;
+const DEFAULT_DYNAMIC_TEXT_COLOR: bool = false;
+/// Seconds between checks for changed settings to save
+const SETTINGS_SAVE_INTERVAL: f32 = 1.0;
 const MAX_FPS: u8 = 240;
 // const DEFAULT_VISUALIZER: VisualizerSelection = VisualizerSelection::ColorTransition;
 // const DEFAULT_VISUALIZER: VisualizerSelection = VisualizerSelection::Tiles;
@@ -56,6 +78,19 @@ enum View {
 struct ReadingViewState {
     doc_index: usize,
     analysis: usize,
+    /// Page of the paragraph being read. Past the last page shows the last page.
+    page: usize,
+    /// How the paragraph was laid out when last drawn
+    layout: Option<ParagraphLayout>,
+    /// Colors of the document's emotional fingerprint strip, one per paragraph. Left empty
+    /// until the reading view is drawn.
+    fingerprint: Vec<Color>,
+    /// How the fingerprint's colors were blended
+    fingerprint_strategy: BlendStrategy,
+    /// Lines of the paragraph with their emotion colors, for the read-along highlight. Left
+    /// empty until the paragraph is drawn with read-along on.
+    lines: Vec<ReadAlongLine>,
+    cursor: ReadAlongCursor,
 }
 
 impl Default for ReadingViewState {
@@ -63,6 +98,12 @@ impl Default for ReadingViewState {
         Self {
             doc_index: 0,
             analysis: 0,
+            page: 0,
+            layout: None,
+            fingerprint: vec![],
+            fingerprint_strategy: BlendStrategy::Weighted,
+            lines: vec![],
+            cursor: ReadAlongCursor::default(),
         }
     }
 }
@@ -71,13 +112,39 @@ impl Default for ReadingViewState {
 struct State {
     view: View,
     show_analysis: bool,
-    emodocs: Vec<EmocatOutputDoc>,
+    /// Highlights the paragraph's lines in turn, at reading pace
+    read_along: bool,
+    /// Scores the emotions of single lines for the read-along highlight
+    lexicon: Lexicon,
+    /// Turns pages on its own and hides the UI, looping through the library
+    presenting: bool,
+    presentation: Presentation,
+    /// Settings as last saved, see `State::save_settings()`
+    settings: ReaderSettings,
+    /// Seconds since settings were last checked for changes
+    settings_elapsed: f32,
+    /// Where the reader is, or was before leaving the reading view. Cleared on returning to
+    /// the poem listing with 'm'.
+    last_read: Option<ReadPosition>,
+    /// Whether the text color is chosen against the background as it transitions, rather
+    /// than against the color it transitions to
+    dynamic_text_color: bool,
+    library: DocumentLibrary,
+    arcs: Vec<EmotionArc>,
+    /// Analyzer the arcs were built with, see `State::sync_arcs()`
+    arcs_source: AnalyzerSource,
+    /// Outcome of the last attempt to load a document at runtime
+    library_message: Option<String>,
+    #[cfg(target_arch = "wasm32")]
+    file_input: Option<FileInput>,
     reading: ReadingViewState,
     font: Font,
     title_font: Font,
     egui_fonts: FontDefinitions,
     selected_visualizer: VisualizerSelection,
     visualizer: Box<dyn EmoVisualizerFull>,
+    color_mappings: ColorMappingRegistry,
+    selected_color_mapping: String,
     needs_handle_resize: bool,
     needs_egui_font_setup: bool,
     touch: TouchState,
@@ -86,45 +153,341 @@ struct State {
 }
 
 impl State {
+    fn apply_color_mapping(&mut self) {
+        if let Some(mapping) = self.color_mappings.get(&self.selected_color_mapping) {
+            self.visualizer.set_color_mapping(mapping.clone());
+        }
+        self.reading.fingerprint.clear();
+        self.reading.lines.clear();
+    }
+
+    /// Parses a document loaded at runtime and adds it to the library
+    fn add_document(&mut self, file_name: &str, data: &[u8]) {
+        match parse_document(file_name, data) {
+            Ok(doc) => {
+                self.library_message = Some(format!("Added \"{}\"", doc.title));
+                self.library.add(doc);
+                self.arcs = build_arcs(self.library.docs(), self.arcs_source);
+                // The document being read may have been replaced
+                self.relocate_reading();
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.library_message = Some(err.to_string());
+            }
+        }
+    }
+
+    /// Registers a user-defined color mapping, see `ColorMappingTable`
+    fn add_color_mapping(&mut self, file_name: &str, data: &[u8]) {
+        let table = match std::str::from_utf8(data) {
+            Ok(source) => ColorMappingTable::from_file_contents(file_name, source)
+                .map_err(|err| format!("{}: {}", file_name, err)),
+            Err(_) => Err(format!("{} is not a text file", file_name)),
+        };
+        match table {
+            Ok(table) => {
+                self.library_message = Some(format!("Added color mapping \"{}\"", table.name));
+                self.color_mappings.register(table);
+                save_color_mappings(&self.color_mappings);
+                // The selected mapping may have been replaced
+                self.apply_color_mapping();
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.library_message = Some(err);
+            }
+        }
+    }
+
+    /// Adds a file the user dropped or picked, which is either a document or a color mapping
+    fn add_file(&mut self, file_name: &str, data: &[u8]) {
+        if is_color_mapping_file(file_name, data) {
+            self.add_color_mapping(file_name, data);
+        } else {
+            self.add_document(file_name, data);
+        }
+    }
+
+    fn remove_document(&mut self, doc_index: usize) {
+        self.library.remove(doc_index);
+        self.arcs = build_arcs(self.library.docs(), self.arcs_source);
+        self.library_message = None;
+        self.relocate_reading();
+    }
+
+    /// Finds the document being read again by its title and author after the library
+    /// changed, as the documents after a removed one move up, or forgets it if it was removed.
+    /// The paragraph is clamped in case the document was replaced by a shorter one.
+    fn relocate_reading(&mut self) {
+        let located = match &self.last_read {
+            Some(position) => position.locate(self.library.docs()),
+            None => None,
+        };
+        match located {
+            Some((doc_index, analysis)) => {
+                self.reading.doc_index = doc_index;
+                self.reading.fingerprint.clear();
+                self.reading.lines.clear();
+                if analysis != self.reading.analysis {
+                    self.set_read_position(analysis, 0);
+                    self.reset_visualizer();
+                }
+            }
+            None => {
+                self.reading = ReadingViewState::default();
+                self.last_read = None;
+            }
+        }
+    }
+
+    fn load_dropped_file(&mut self, file: DroppedFile) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = &file.path {
+            match std::fs::read(path) {
+                Ok(data) => self.add_file(&file.name, &data),
+                Err(err) => {
+                    self.library_message = Some(format!("Could not read {}: {}", file.name, err))
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let (Some(file_input), Some(web_file)) = (&self.file_input, file.file) {
+            // Read asynchronously, and picked up in update()
+            file_input.read_file(web_file);
+        }
+    }
+
+    fn update_fingerprint(&mut self) {
+        if let Some(mapping) = self.color_mappings.get(&self.selected_color_mapping) {
+            let strategy = self.visualizer.get_blend_strategy();
+            self.reading.fingerprint =
+                self.arcs[self.reading.doc_index].fingerprint(mapping, strategy);
+            self.reading.fingerprint_strategy = strategy;
+        }
+    }
+
+    /// Rebuilds the arcs and the fingerprint when the visualizer's analyzer or blend strategy
+    /// changed, such as in the Options view or after switching visualizers
+    fn sync_arcs(&mut self) {
+        let source = self.visualizer.get_analyzer_source();
+        if source != self.arcs_source {
+            self.arcs_source = source;
+            self.arcs = build_arcs(self.library.docs(), source);
+            self.reading.fingerprint.clear();
+        }
+        if self.visualizer.get_blend_strategy() != self.reading.fingerprint_strategy {
+            self.reading.fingerprint.clear();
+        }
+    }
+
+    fn update_read_along_lines(&mut self) {
+        if let Some(mapping) = self.color_mappings.get(&self.selected_color_mapping) {
+            let emodoc = &self.library.docs()[self.reading.doc_index];
+            self.reading.lines = read_along_lines(
+                &emodoc.analyses[self.reading.analysis - 1],
+                &self.lexicon,
+                mapping,
+            );
+        }
+    }
+
+    /// Moves to a page of a paragraph, or to the title with `analysis` 0, forgetting what was
+    /// derived from the previous paragraph
+    fn set_read_position(&mut self, analysis: usize, page: usize) {
+        if analysis != self.reading.analysis {
+            self.reading.layout = None;
+            self.reading.lines.clear();
+            self.reading.cursor.restart(0);
+        }
+        self.reading.analysis = analysis;
+        self.reading.page = page;
+        self.presentation.restart();
+        let emodoc = &self.library.docs()[self.reading.doc_index];
+        self.last_read = Some(ReadPosition::new(emodoc, analysis));
+    }
+
+    /// Starts reading a document from the title, or carries on reading the document that was
+    /// being read
+    fn open_document(&mut self, doc_index: usize) {
+        self.view = View::READ;
+        let located = match &self.last_read {
+            Some(position) => position.locate(self.library.docs()),
+            None => None,
+        };
+        if located.map(|(index, _)| index) != Some(doc_index) {
+            self.reading.doc_index = doc_index;
+            self.reading.fingerprint.clear();
+            self.goto_read_home();
+        }
+    }
+
+    /// Resets the visualizer to the paragraph being read, such as after the dynamic text color
+    /// setting changes
+    fn reset_visualizer(&mut self) {
+        self.visualizer
+            .reset(CLEAR_COLOR, TITLE_COLOR, self.dynamic_text_color);
+        if self.reading.analysis > 0 {
+            let emodoc = &self.library.docs()[self.reading.doc_index];
+            self.visualizer
+                .update_model(&emodoc.analyses[self.reading.analysis - 1]);
+        }
+    }
+
+    fn current_settings(&mut self) -> ReaderSettings {
+        let mut visualizer_options = self.settings.visualizer_options.clone();
+        visualizer_options.insert(self.visualizer.get_enum(), self.visualizer.save_options());
+        ReaderSettings {
+            version: SETTINGS_VERSION,
+            visualizer: self.selected_visualizer,
+            visualizer_options,
+            dynamic_text_color: self.dynamic_text_color,
+            color_mapping: self.selected_color_mapping.clone(),
+            presentation_wpm: self.presentation.wpm,
+            last_read: self.last_read.clone(),
+        }
+    }
+
+    /// Saves the settings if they changed since they were last saved
+    fn save_settings(&mut self) {
+        let settings = self.current_settings();
+        if settings != self.settings {
+            settings.save();
+            self.settings = settings;
+        }
+    }
+
+    /// Applies the options saved for the current visualizer, such as after switching to it
+    fn restore_visualizer_options(&mut self) {
+        if let Some(options) = self
+            .settings
+            .visualizer_options
+            .get(&self.visualizer.get_enum())
+        {
+            self.visualizer.restore_options(options);
+        }
+    }
+
+    /// Moves to where the reader was in the previous session, if the document is still in
+    /// the library
+    fn restore_read_position(&mut self) {
+        let located = match &self.settings.last_read {
+            Some(position) => position.locate(self.library.docs()),
+            None => None,
+        };
+        if let Some((doc_index, analysis)) = located {
+            self.view = View::READ;
+            self.reading.doc_index = doc_index;
+            self.set_read_position(analysis, 0);
+            self.reset_visualizer();
+        }
+    }
+
+    /// Words on the page being read, estimated for paragraphs split into pages
+    fn page_words(&self) -> usize {
+        let emodoc = &self.library.docs()[self.reading.doc_index];
+        if self.reading.analysis == 0 {
+            return emodoc.title.split_whitespace().count()
+                + emodoc.author.split_whitespace().count();
+        }
+        let words = emodoc.analyses[self.reading.analysis - 1]
+            .text
+            .split_whitespace()
+            .count();
+        match &self.reading.layout {
+            Some(layout) => words.div_ceil(layout.pages.max(1)),
+            None => words,
+        }
+    }
+
+    fn set_presenting(&mut self, app: &mut App, presenting: bool) {
+        self.presenting = presenting;
+        self.presentation.restart();
+        if presenting {
+            self.show_analysis = false;
+            self.help_modal.show_help = false;
+            self.help_modal.show_touch_help = false;
+            app.window().set_cursor(notan::prelude::CursorIcon::None);
+        } else {
+            app.window().set_cursor(notan::prelude::CursorIcon::Default);
+        }
+    }
+
+    /// Turns the page in presentation mode, moving on to the title of the next document in
+    /// the library, or back to the first, after the last page of a document
+    fn present_next(&mut self) {
+        let analyses = self.library.docs()[self.reading.doc_index].analyses.len();
+        let on_last_page = match &self.reading.layout {
+            Some(layout) => layout.page + 1 >= layout.pages,
+            None => true,
+        };
+        if self.reading.analysis < analyses || !on_last_page {
+            self.goto_read_next();
+            return;
+        }
+        self.reading.doc_index = (self.reading.doc_index + 1) % self.library.docs().len();
+        self.reading.fingerprint.clear();
+        self.goto_read_home();
+    }
+
     fn goto_home_view(&mut self) {
         self.view = View::HOME;
         self.reading = ReadingViewState::default();
+        self.last_read = None;
         self.visualizer
-            .reset(CLEAR_COLOR, TITLE_COLOR, DYNAMIC_TEXT_COLOR);
+            .reset(CLEAR_COLOR, TITLE_COLOR, self.dynamic_text_color);
     }
 
     fn goto_read_home(&mut self) {
-        self.reading.analysis = 0;
+        self.set_read_position(0, 0);
         self.visualizer
-            .gracefully_reset(CLEAR_COLOR, TITLE_COLOR, DYNAMIC_TEXT_COLOR);
+            .gracefully_reset(CLEAR_COLOR, TITLE_COLOR, self.dynamic_text_color);
     }
 
     fn goto_read_end(&mut self) {
-        let emodoc = &self.emodocs[self.reading.doc_index];
-        self.reading.analysis = emodoc.analyses.len();
+        let last = self.library.docs()[self.reading.doc_index].analyses.len();
+        self.set_read_position(last, 0);
+        let emodoc = &self.library.docs()[self.reading.doc_index];
         self.visualizer
             .update_model(&emodoc.analyses[self.reading.analysis - 1]);
     }
 
     fn goto_read_next(&mut self) {
-        let emodoc = &self.emodocs[self.reading.doc_index];
-        if self.reading.analysis < emodoc.analyses.len() {
-            self.reading.analysis += 1;
+        if let Some(layout) = &self.reading.layout {
+            if layout.page + 1 < layout.pages {
+                let page = layout.page + 1;
+                self.set_read_position(self.reading.analysis, page);
+                return;
+            }
+        }
+        let analyses = self.library.docs()[self.reading.doc_index].analyses.len();
+        if self.reading.analysis < analyses {
+            self.set_read_position(self.reading.analysis + 1, 0);
+            let emodoc = &self.library.docs()[self.reading.doc_index];
             self.visualizer
                 .update_model(&emodoc.analyses[self.reading.analysis - 1]);
         }
     }
 
     fn goto_read_prev(&mut self) {
+        if let Some(layout) = &self.reading.layout {
+            if layout.page > 0 {
+                let page = layout.page - 1;
+                self.set_read_position(self.reading.analysis, page);
+                return;
+            }
+        }
         if self.reading.analysis > 0 {
-            let emodoc = &self.emodocs[self.reading.doc_index];
-            self.reading.analysis -= 1;
+            // Back to the last page of the previous paragraph
+            self.set_read_position(self.reading.analysis - 1, usize::MAX);
+            let emodoc = &self.library.docs()[self.reading.doc_index];
             if self.reading.analysis > 0 {
                 self.visualizer
                     .update_model(&emodoc.analyses[self.reading.analysis - 1]);
             } else {
                 self.visualizer
-                    .gracefully_reset(CLEAR_COLOR, TITLE_COLOR, DYNAMIC_TEXT_COLOR);
+                    .gracefully_reset(CLEAR_COLOR, TITLE_COLOR, self.dynamic_text_color);
             }
         }
     }
@@ -159,6 +522,12 @@ fn configure_egui_fonts(title_font_bytes: &'static [u8]) -> FontDefinitions {
     egui_fonts
 }
 
+fn build_arcs(docs: &[EmocatOutputDoc], source: AnalyzerSource) -> Vec<EmotionArc> {
+    docs.iter()
+        .map(|doc| EmotionArc::from_doc(doc, source))
+        .collect()
+}
+
 fn init(gfx: &mut Graphics) -> State {
     let font_bytes = include_bytes!(
         // "./assets/fonts/Ubuntu-B.ttf"
@@ -177,11 +546,19 @@ fn init(gfx: &mut Graphics) -> State {
         .iter()
         .map(|&doc| serde_json::from_str(doc).expect("Could not open emocat document"))
         .collect();
+    let library = DocumentLibrary::new(emodocs);
+    let arcs_source = AnalyzerSource::default();
+    let arcs = build_arcs(library.docs(), arcs_source);
+
+    let mut color_mappings = ColorMappingRegistry::default();
+    restore_color_mappings(&mut color_mappings);
 
     let help_text = concat!(
         // "Use \u{00AB} left or right \u{00BB} arrow keys to read poem\n\n",
         "Use left or right arrow keys to read through the piece\n\n",
         "Press 'a' to toggle Analysis panel\n\n",
+        "Press 'h' to highlight lines as you read along\n\n",
+        "Press 'p' to present, turning pages on their own\n\n",
         "Press 'm' to return to poem listing\n\n",
         "Click mouse to close this help",
     );
@@ -199,35 +576,56 @@ fn init(gfx: &mut Graphics) -> State {
         .build()
         .unwrap();
 
-    let state = State {
+    let settings = ReaderSettings::load(ReaderSettings {
+        version: SETTINGS_VERSION,
+        visualizer: DEFAULT_VISUALIZER,
+        visualizer_options: HashMap::new(),
+        dynamic_text_color: DEFAULT_DYNAMIC_TEXT_COLOR,
+        color_mapping: DEFAULT_COLOR_MAPPING.to_string(),
+        presentation_wpm: DEFAULT_PRESENTATION_WPM,
+        last_read: None,
+    });
+
+    let mut state = State {
         view: View::HOME,
         // view: View::READ,
         show_analysis: false,
-        emodocs,
+        read_along: false,
+        lexicon: Lexicon::default(),
+        presenting: false,
+        presentation: Presentation::new(settings.presentation_wpm),
+        library,
+        arcs,
+        arcs_source,
+        library_message: None,
+        #[cfg(target_arch = "wasm32")]
+        file_input: FileInput::new(),
         reading: ReadingViewState::default(),
         font,
         title_font,
         egui_fonts,
-        selected_visualizer: DEFAULT_VISUALIZER,
-        visualizer: match DEFAULT_VISUALIZER {
+        selected_visualizer: settings.visualizer,
+        visualizer: match settings.visualizer {
             VisualizerSelection::TiledShaders => Box::new(TiledShadersVisualizer::new(
                 gfx,
                 CLEAR_COLOR,
                 TITLE_COLOR,
-                DYNAMIC_TEXT_COLOR,
+                settings.dynamic_text_color,
             )),
             VisualizerSelection::Tiles => Box::new(TilesVisualizer::new(
                 CLEAR_COLOR,
                 TITLE_COLOR,
-                DYNAMIC_TEXT_COLOR,
+                settings.dynamic_text_color,
                 tile_texture.clone(),
             )),
             _ => Box::new(ColorTransitionVisualizer::new(
                 CLEAR_COLOR,
                 TITLE_COLOR,
-                DYNAMIC_TEXT_COLOR,
+                settings.dynamic_text_color,
             )),
         },
+        color_mappings,
+        selected_color_mapping: settings.color_mapping.clone(),
         needs_handle_resize: true,
         needs_egui_font_setup: true,
         touch: TouchState::default(),
@@ -238,7 +636,22 @@ fn init(gfx: &mut Graphics) -> State {
             None,
         ),
         tile_texture,
+        dynamic_text_color: settings.dynamic_text_color,
+        last_read: None,
+        settings_elapsed: 0.0,
+        settings,
     };
+    if state
+        .color_mappings
+        .get(&state.selected_color_mapping)
+        .is_none()
+    {
+        state.selected_color_mapping = DEFAULT_COLOR_MAPPING.to_string();
+    }
+    state.apply_color_mapping();
+    state.restore_visualizer_options();
+    state.sync_arcs();
+    state.restore_read_position();
     state
 }
 
@@ -268,7 +681,40 @@ fn update_read_view(app: &mut App, state: &mut State) {
         state.show_analysis = !state.show_analysis;
     }
 
-    state.visualizer.update_visualization();
+    if app.keyboard.was_pressed(KeyCode::KeyH) {
+        log::debug!("h");
+        state.read_along = !state.read_along;
+        state.reading.cursor.restart(0);
+    }
+
+    if app.keyboard.was_pressed(KeyCode::KeyP) {
+        log::debug!("p");
+        let presenting = !state.presenting;
+        state.set_presenting(app, presenting);
+    }
+
+    if state.presenting {
+        let words = state.page_words();
+        let transition_complete = state.visualizer.is_transition_complete();
+        if state
+            .presentation
+            .step(app.timer.delta_f32(), words, transition_complete)
+        {
+            state.present_next();
+        }
+    }
+
+    if state.read_along {
+        if let Some(layout) = &state.reading.layout {
+            state.reading.cursor.step(
+                app.timer.delta_f32(),
+                &state.reading.lines,
+                layout.lines.clone(),
+            );
+        }
+    }
+
+    state.visualizer.update_visualization(app.timer.delta_f32());
 }
 
 fn handle_read_view_touch_events(app: &mut App, state: &mut State, evt: Event) {
@@ -299,11 +745,34 @@ fn handle_read_view_touch_events(app: &mut App, state: &mut State, evt: Event) {
 }
 
 fn update(app: &mut App, state: &mut State) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let loaded = match &state.file_input {
+            Some(file_input) => file_input.take_loaded(),
+            None => vec![],
+        };
+        for (file_name, data) in loaded {
+            state.add_file(&file_name, &data);
+        }
+    }
+
     if app.keyboard.was_pressed(KeyCode::KeyM) {
         log::debug!("m");
         state.goto_home_view();
     }
 
+    if state.presenting && state.view != View::READ {
+        state.set_presenting(app, false);
+    }
+
+    state.sync_arcs();
+
+    state.settings_elapsed += app.timer.delta_f32();
+    if state.settings_elapsed >= SETTINGS_SAVE_INTERVAL {
+        state.settings_elapsed = 0.0;
+        state.save_settings();
+    }
+
     match state.view {
         View::READ => update_read_view(app, state),
         _ => (),
@@ -311,7 +780,7 @@ fn update(app: &mut App, state: &mut State) {
 }
 
 fn draw_title(draw: &mut Draw, state: &mut State, work_size: Vec2) {
-    let emodoc = &state.emodocs[state.reading.doc_index];
+    let emodoc = &state.library.docs()[state.reading.doc_index];
     state.visualizer.draw_title(
         draw,
         &state.title_font,
@@ -322,13 +791,71 @@ fn draw_title(draw: &mut Draw, state: &mut State, work_size: Vec2) {
 }
 
 fn draw_paragraph(draw: &mut Draw, state: &mut State, work_size: Vec2) {
-    let emodoc = &state.emodocs[state.reading.doc_index];
-    state.visualizer.draw_paragraph(
+    let mut highlight = None;
+    if state.read_along {
+        if state.reading.lines.is_empty() {
+            state.update_read_along_lines();
+        }
+        let line = state.reading.cursor.line;
+        highlight = state
+            .reading
+            .lines
+            .get(line)
+            .map(|read_along_line| LineHighlight {
+                line,
+                color: read_along_line.color,
+            });
+    }
+    let emodoc = &state.library.docs()[state.reading.doc_index];
+    let layout = state.visualizer.draw_paragraph(
         draw,
         &state.font,
         &emodoc.analyses[state.reading.analysis - 1].text,
+        state.reading.page,
+        highlight,
         work_size,
     );
+    state.reading.page = layout.page;
+    if layout.pages > 1 && !state.presenting {
+        draw.text(
+            &state.font,
+            &format!("{} / {}", layout.page + 1, layout.pages),
+        )
+        .alpha_mode(BlendMode::OVER)
+        .color(state.visualizer.get_text_color())
+        .alpha(0.6)
+        .size(scale_font(16.0, work_size))
+        .position(work_size.x * 0.875, work_size.y * 0.9)
+        .h_align_right()
+        .v_align_middle();
+    }
+    state.reading.layout = Some(layout);
+}
+
+/// Draws the document's emotional fingerprint along the bottom of the screen, raising the
+/// paragraph being read
+fn draw_fingerprint_strip(draw: &mut Draw, state: &mut State, work_size: Vec2) {
+    if state.reading.fingerprint.is_empty() {
+        state.update_fingerprint();
+    }
+    let fingerprint = &state.reading.fingerprint;
+    if fingerprint.is_empty() {
+        return;
+    }
+    let width = work_size.x / fingerprint.len() as f32;
+    let height = work_size.y * 0.01;
+    for (i, color) in fingerprint.iter().enumerate() {
+        let strip_height = if i + 1 == state.reading.analysis {
+            height * 3.0
+        } else {
+            height
+        };
+        draw.rect(
+            (i as f32 * width, work_size.y - strip_height),
+            (width, strip_height),
+        )
+        .color(*color);
+    }
 }
 
 fn draw_read_help(draw: &mut Draw, state: &mut State, work_size: Vec2) {
@@ -368,11 +895,19 @@ fn draw_read_view(
     } else {
         draw_paragraph(draw, state, work_size);
     }
-    draw_read_help(draw, state, work_size);
+    // Presentations show nothing but the piece
+    if !state.presenting {
+        if state.show_analysis {
+            draw_fingerprint_strip(draw, state, work_size);
+        }
+        draw_read_help(draw, state, work_size);
+    }
     gfx.render(draw);
 
     let output = plugins.egui(|ctx| {
-        draw_analysis_panel(ctx, state, work_size);
+        if !state.presenting {
+            draw_analysis_panel(ctx, state, work_size);
+        }
     });
     // Not checking if output needs repaint because then the analysis panel
     // is erased by the draw above
@@ -483,6 +1018,9 @@ fn event(app: &mut App, state: &mut State, evt: Event) {
             log::debug!("Window resized to: w {}, h {}", width, height);
             state.needs_handle_resize = true
         }
+        Event::Drop(ref file) if state.view == View::HOME => {
+            state.load_dropped_file(file.clone());
+        }
         _ => {}
     }
 
@@ -720,6 +1258,43 @@ fn draw_settings_view(
                         });
                 });
 
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
+                    ui.label("Color Mapping");
+                    let previous_mapping = state.selected_color_mapping.clone();
+                    egui::ComboBox::new("selected-color-mapping", "")
+                        .selected_text(&state.selected_color_mapping)
+                        .wrap()
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                            for name in state.color_mappings.names() {
+                                ui.selectable_value(
+                                    &mut state.selected_color_mapping,
+                                    name.to_string(),
+                                    name,
+                                );
+                            }
+                        });
+                    if state.selected_color_mapping != previous_mapping {
+                        state.apply_color_mapping();
+                    }
+                });
+
+                if ui
+                    .checkbox(&mut state.dynamic_text_color, "Dynamic Text Color")
+                    .changed()
+                {
+                    state.reset_visualizer();
+                }
+
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
+                    ui.label("Presentation Pace");
+                    ui.add(
+                        egui::Slider::new(&mut state.presentation.wpm, 60.0..=300.0)
+                            .suffix(" wpm")
+                            .max_decimals(0),
+                    );
+                });
+
                 heading_frame = egui::Frame::NONE
                     .fill(ui_fill)
                     .inner_margin(egui::Vec2::new(0.0, margin * 2.0));
@@ -772,7 +1347,9 @@ fn draw_home_view(gfx: &mut Graphics, plugins: &mut Plugins, state: &mut State,
                                 egui::Vec2::new(button_padding_x, button_padding_y);
                             ctx.set_style(style);
 
-                            for (doc_index, emodoc) in state.emodocs.iter().enumerate() {
+                            let mut open_doc_index = None;
+                            let mut remove_doc_index = None;
+                            for (doc_index, emodoc) in state.library.docs().iter().enumerate() {
                                 // ui.heading(&emodoc.title);
                                 let title_text = RichText::new(&emodoc.title)
                                     .color(egui::Color32::WHITE)
@@ -783,15 +1360,42 @@ fn draw_home_view(gfx: &mut Graphics, plugins: &mut Plugins, state: &mut State,
                                     .fill(egui::Color32::GRAY);
                                 title_frame.show(ui, |ui| {
                                     if ui.add(title_button).clicked() {
-                                        state.reading.doc_index = doc_index;
-                                        state.view = View::READ;
+                                        open_doc_index = Some(doc_index);
                                         log::debug!("{}", &emodoc.title);
                                     }
                                     let author_text = RichText::new(&emodoc.author)
                                         .text_style(author_menu_text());
                                     ui.label(author_text);
+                                    if state.library.is_user_doc(doc_index)
+                                        && ui.small_button("Remove").clicked()
+                                    {
+                                        remove_doc_index = Some(doc_index);
+                                    }
                                 });
                             }
+                            if let Some(doc_index) = open_doc_index {
+                                state.open_document(doc_index);
+                            }
+                            if let Some(doc_index) = remove_doc_index {
+                                state.remove_document(doc_index);
+                            }
+
+                            title_frame.show(ui, |ui| {
+                                #[cfg(target_arch = "wasm32")]
+                                if let Some(file_input) = &state.file_input {
+                                    if ui.button("Add your own...").clicked() {
+                                        file_input.open();
+                                    }
+                                }
+                                ui.small(
+                                    "Drop emocat .json files or plain .txt files on this window \
+                                     to add them to the list, or color mapping .toml or .json \
+                                     files to add them to the Options",
+                                );
+                                if let Some(message) = &state.library_message {
+                                    ui.small(message);
+                                }
+                            });
                         });
                     });
             },
@@ -806,6 +1410,8 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
     let work_size = get_work_size(gfx);
 
     if state.selected_visualizer != state.visualizer.get_enum() {
+        // Keeps the options of the visualizer being switched from
+        state.save_settings();
         match state.selected_visualizer {
             VisualizerSelection::TiledShaders => {
                 log::debug!("swap to TiledShadersVisualizer");
@@ -813,7 +1419,7 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                     gfx,
                     CLEAR_COLOR,
                     TITLE_COLOR,
-                    DYNAMIC_TEXT_COLOR,
+                    state.dynamic_text_color,
                 ));
             }
             VisualizerSelection::Tiles => {
@@ -821,7 +1427,7 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                 state.visualizer = Box::new(TilesVisualizer::new(
                     CLEAR_COLOR,
                     TITLE_COLOR,
-                    DYNAMIC_TEXT_COLOR,
+                    state.dynamic_text_color,
                     state.tile_texture.clone(),
                 ));
             }
@@ -830,10 +1436,12 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                 state.visualizer = Box::new(ColorTransitionVisualizer::new(
                     CLEAR_COLOR,
                     TITLE_COLOR,
-                    DYNAMIC_TEXT_COLOR,
+                    state.dynamic_text_color,
                 ));
             }
         }
+        state.apply_color_mapping();
+        state.restore_visualizer_options();
     }
 
     match state.view {
@@ -136,7 +136,7 @@ fn update(app: &mut App, state: &mut State) {
             .visualizer
             .update_model(&state.emodoc.analyses[state.analysis - 1]);
     }
-    state.visualizer.update_visualization();
+    state.visualizer.update_visualization(app.timer.delta_f32());
 }
 
 fn draw_title(draw: &mut Draw, state: &mut State, work_size: Vec2) {
@@ -154,6 +154,8 @@ fn draw_paragraph(draw: &mut Draw, state: &mut State, work_size: Vec2) {
         draw,
         &state.font,
         &state.emodoc.analyses[state.analysis - 1].text,
+        0,
+        None,
         work_size,
     );
 }
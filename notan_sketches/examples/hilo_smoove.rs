@@ -10,7 +10,9 @@ use notan_sketches::mathutils::mid;
 #[cfg(not(debug_assertions))]
 use notan_sketches::shaderutils::create_shape_pipeline;
 #[cfg(debug_assertions)]
-use notan_sketches::shaderutils::{create_hot_shape_pipeline, ShaderReloadManager};
+use notan_sketches::shaderutils::{
+    create_hot_shape_pipeline, ShaderReloadManager, SHAPES_VERT_PATH,
+};
 use notan_sketches::shaderutils::{CommonData, ShaderRenderTexture};
 use notan_sketches::utils::{
     get_common_win_config, get_draw_setup, get_rng, get_work_size_for_screen, set_html_bgcolor,
@@ -187,9 +189,12 @@ fn init(app: &mut App, gfx: &mut Graphics) -> State {
     #[cfg(not(debug_assertions))]
     let shader_pipeline = create_shape_pipeline(gfx, Some(&FRAG)).unwrap();
     #[cfg(debug_assertions)]
-    let shader_pipeline =
-        create_hot_shape_pipeline(gfx, "examples/assets/shaders/horizontal_city.frag.glsl")
-            .unwrap();
+    let shader_pipeline = create_hot_shape_pipeline(
+        gfx,
+        SHAPES_VERT_PATH,
+        "examples/assets/shaders/horizontal_city.frag.glsl",
+    )
+    .unwrap();
 
     let shader_ubo = gfx
         .create_uniform_buffer(1, "Common")
@@ -212,7 +217,10 @@ fn init(app: &mut App, gfx: &mut Graphics) -> State {
         shader_pipeline,
         shader_ubo,
         #[cfg(debug_assertions)]
-        hot_mgr: ShaderReloadManager::default(),
+        hot_mgr: ShaderReloadManager::new(&[
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/horizontal_city.frag.glsl",
+        ]),
     }
 }
 
@@ -597,7 +605,7 @@ fn draw_shader_strip(
             vec![shader_ubo]
         };
 
-        shader_rt.draw(gfx, shader_pipeline, ubos, |shader_draw| {
+        if let Err(err) = shader_rt.draw(gfx, shader_pipeline, &ubos, |shader_draw| {
             let path = &mut shader_draw.path();
             path.move_to(0.0, ypos);
 
@@ -631,7 +639,9 @@ fn draw_shader_strip(
                 }
             }
             path.close().fill_color(strip.color).fill();
-        });
+        }) {
+            log::error!("{}", err);
+        }
 
         // Draw the shader texture
         draw.image(&shader_rt.rt)
@@ -657,7 +667,11 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
 
     #[cfg(debug_assertions)]
     if state.hot_mgr.needs_reload() {
-        match create_hot_shape_pipeline(gfx, "examples/assets/shaders/horizontal_city.frag.glsl") {
+        match create_hot_shape_pipeline(
+            gfx,
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/horizontal_city.frag.glsl",
+        ) {
             Ok(pipeline) => state.shader_pipeline = pipeline,
             Err(err) => log::error!("{}", err),
         }
@@ -4,6 +4,7 @@ use notan::math::Vec2;
 use notan::prelude::*;
 use notan_sketches::shaderutils::{
     create_hot_shape_pipeline, CommonData, ShaderReloadManager, ShaderRenderTexture,
+    SHAPES_VERT_PATH,
 };
 use notan_sketches::utils::{get_common_win_config, get_draw_setup, ScreenDimensions};
 
@@ -19,9 +20,12 @@ struct State {
 }
 
 fn init(gfx: &mut Graphics) -> State {
-    let pipeline =
-        create_hot_shape_pipeline(gfx, "examples/assets/shaders/horizontal_city.frag.glsl")
-            .unwrap();
+    let pipeline = create_hot_shape_pipeline(
+        gfx,
+        SHAPES_VERT_PATH,
+        "examples/assets/shaders/horizontal_city.frag.glsl",
+    )
+    .unwrap();
 
     let common_data = CommonData::new(0.0, WORK_SIZE);
     let common_ubo = gfx
@@ -37,7 +41,10 @@ fn init(gfx: &mut Graphics) -> State {
         common_ubo,
         srt,
         #[cfg(debug_assertions)]
-        hot_mgr: ShaderReloadManager::default(),
+        hot_mgr: ShaderReloadManager::new(&[
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/horizontal_city.frag.glsl",
+        ]),
     }
 }
 
@@ -54,7 +61,11 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
 
     #[cfg(debug_assertions)]
     if state.hot_mgr.needs_reload() {
-        match create_hot_shape_pipeline(gfx, "examples/assets/shaders/horizontal_city.frag.glsl") {
+        match create_hot_shape_pipeline(
+            gfx,
+            SHAPES_VERT_PATH,
+            "examples/assets/shaders/horizontal_city.frag.glsl",
+        ) {
             Ok(pipeline) => state.pipeline = pipeline,
             Err(err) => log::error!("{}", err),
         }
@@ -66,9 +77,12 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
             .unwrap();
     }
 
-    state
+    if let Err(err) = state
         .srt
-        .draw_filled(gfx, &state.pipeline, vec![&state.common_ubo]);
+        .draw_filled(gfx, &state.pipeline, &[&state.common_ubo])
+    {
+        log::error!("{}", err);
+    }
 
     // Draw shader in a rectangle at the center
     let rect_size = 800.0;
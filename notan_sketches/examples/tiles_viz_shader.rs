@@ -51,7 +51,7 @@ fn draw(app: &mut App, gfx: &mut Graphics, state: &mut State) {
         // tile.srt.draw_filled(
         //     gfx,
         //     &state.pipeline,
-        //     vec![&tile.common_ubo, &tile.tile_colors_ubo],
+        //     &[&tile.common_ubo, &tile.tile_colors_ubo],
         // );
     }
 
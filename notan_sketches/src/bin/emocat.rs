@@ -0,0 +1,171 @@
+use notan_sketches::emotion::analyzer::{Lexicon, ParagraphSplit, TextAnalyzer};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "\
+Converts plain text into emocat JSON documents for emo_bg_visualizer
+
+Usage: emocat [OPTIONS] <INPUT>
+
+<INPUT> is a .txt file, or a directory whose .txt files are all converted.
+
+Options:
+  -o, --output <PATH>   Output file for a single input, or output directory for a directory
+                        input. Defaults to writing <name>.json next to each input file
+  --title <TITLE>       Title of the document. Defaults to the input file name. Only allowed
+                        for a single input file
+  --author <AUTHOR>     Author of the document(s) [default: Unknown]
+  --split <RULE>        How to split the text into paragraphs: `blank-lines` (paragraphs or
+                        stanzas separated by blank lines), `lines` (every line), or a number
+                        of lines per paragraph [default: blank-lines]
+  --lexicon <PATH>      Lexicon in the NRC Emotion Lexicon format, instead of the bundled one
+  -h, --help            Print this help
+";
+
+struct Args {
+    input: PathBuf,
+    output: Option<PathBuf>,
+    title: Option<String>,
+    author: String,
+    split: ParagraphSplit,
+    lexicon: Option<PathBuf>,
+}
+
+fn parse_split(rule: &str) -> Result<ParagraphSplit, String> {
+    match rule {
+        "blank-lines" => Ok(ParagraphSplit::BlankLines),
+        "lines" => Ok(ParagraphSplit::Lines),
+        _ => match rule.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(ParagraphSplit::FixedLines(n)),
+            _ => Err(format!("invalid --split rule `{}`", rule)),
+        },
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut title = None;
+    let mut author = "Unknown".to_string();
+    let mut split = ParagraphSplit::default();
+    let mut lexicon = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            "-o" | "--output" => output = Some(PathBuf::from(value(&arg)?)),
+            "--title" => title = Some(value(&arg)?),
+            "--author" => author = value(&arg)?,
+            "--split" => split = parse_split(&value(&arg)?)?,
+            "--lexicon" => lexicon = Some(PathBuf::from(value(&arg)?)),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    Ok(Args {
+        input: input.ok_or("missing <INPUT>")?,
+        output,
+        title,
+        author,
+        split,
+        lexicon,
+    })
+}
+
+fn convert(
+    analyzer: &TextAnalyzer,
+    input: &Path,
+    output: &Path,
+    title: Option<&str>,
+    author: &str,
+) -> Result<(), String> {
+    let text = fs::read_to_string(input)
+        .map_err(|e| format!("could not read {}: {}", input.display(), e))?;
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    let title = title.unwrap_or(&stem);
+    let doc = analyzer.analyze(title, author, &input.display().to_string(), &text);
+    if doc.analyses.is_empty() {
+        return Err(format!("{} has no text to analyze", input.display()));
+    }
+    let json = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+    fs::write(output, json).map_err(|e| format!("could not write {}: {}", output.display(), e))?;
+    println!(
+        "{} -> {} ({} paragraphs)",
+        input.display(),
+        output.display(),
+        doc.analyses.len()
+    );
+    Ok(())
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+
+    let lexicon = match &args.lexicon {
+        Some(path) => {
+            let source = fs::read_to_string(path)
+                .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+            Lexicon::from_nrc_str(&source).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => Lexicon::default(),
+    };
+    let analyzer = TextAnalyzer::new(lexicon, args.split);
+
+    if args.input.is_dir() {
+        if args.title.is_some() {
+            return Err("--title can only be used with a single input file".to_string());
+        }
+        let mut inputs: Vec<PathBuf> = fs::read_dir(&args.input)
+            .map_err(|e| format!("could not read {}: {}", args.input.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect();
+        inputs.sort();
+        if inputs.is_empty() {
+            return Err(format!("no .txt files in {}", args.input.display()));
+        }
+        if let Some(dir) = &args.output {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+        }
+        for input in inputs.iter() {
+            let output = match &args.output {
+                Some(dir) => dir.join(input.with_extension("json").file_name().unwrap()),
+                None => input.with_extension("json"),
+            };
+            convert(&analyzer, input, &output, None, &args.author)?;
+        }
+    } else {
+        let output = args
+            .output
+            .clone()
+            .unwrap_or_else(|| args.input.with_extension("json"));
+        convert(
+            &analyzer,
+            &args.input,
+            &output,
+            args.title.as_deref(),
+            &args.author,
+        )?;
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("emocat: {}", err);
+        eprintln!("Run `emocat --help` for usage");
+        process::exit(1);
+    }
+}
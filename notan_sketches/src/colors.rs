@@ -1,3 +1,5 @@
+pub mod contrast;
+
 use notan::app::Color;
 use notan::random::rand::{
     distr::{Distribution, StandardUniform},
@@ -0,0 +1,185 @@
+use notan::app::Color;
+use serde::{Deserialize, Serialize};
+
+/// Minimum WCAG 2.x contrast ratio for normal text at level AA
+pub const WCAG_AA: f32 = 4.5;
+/// Minimum WCAG 2.x contrast ratio for large text at level AA
+pub const WCAG_AA_LARGE: f32 = 3.0;
+/// Minimum WCAG 2.x contrast ratio for normal text at level AAA
+pub const WCAG_AAA: f32 = 7.0;
+/// Minimum APCA Lc, as an absolute value, recommended for body text
+pub const APCA_BODY_TEXT: f32 = 75.0;
+
+// APCA 0.0.98G-4g constants
+const APCA_MAIN_TRC: f32 = 2.4;
+const APCA_NORM_BG: f32 = 0.56;
+const APCA_NORM_TXT: f32 = 0.57;
+const APCA_REV_TXT: f32 = 0.62;
+const APCA_REV_BG: f32 = 0.65;
+const APCA_BLACK_THRESHOLD: f32 = 0.022;
+const APCA_BLACK_CLAMP: f32 = 1.414;
+const APCA_SCALE: f32 = 1.14;
+const APCA_LOW_OFFSET: f32 = 0.027;
+const APCA_LOW_CLIP: f32 = 0.1;
+const APCA_DELTA_Y_MIN: f32 = 0.0005;
+
+
+/// WCAG 2.x relative luminance of the color, from 0.0 for black to 1.0 for white. Alpha is
+/// ignored.
+pub fn relative_luminance(color: &Color) -> f32 {
+    fn linearize(channel: f32) -> f32 {
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    0.2126 * linearize(color.r) + 0.7152 * linearize(color.g) + 0.0722 * linearize(color.b)
+}
+
+
+/// WCAG 2.x contrast ratio between the colors, from 1.0 (none) to 21.0 (black on white).
+/// The order of the colors doesn't matter.
+pub fn contrast_ratio(a: &Color, b: &Color) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+
+/// APCA lightness contrast (Lc) of text on a background, from about 106 for black text on
+/// white to about -108 for white text on black. Positive for dark text on a lighter
+/// background, negative for light text on a darker one, and 0.0 when the contrast is too low
+/// to be readable.
+///
+/// Based on the APCA 0.0.98G-4g reference implementation:
+/// https://github.com/Myndex/apca-w3
+pub fn apca_contrast(text: &Color, bg: &Color) -> f32 {
+    fn screen_luminance(color: &Color) -> f32 {
+        let y = 0.2126729 * color.r.max(0.0).powf(APCA_MAIN_TRC)
+            + 0.7151522 * color.g.max(0.0).powf(APCA_MAIN_TRC)
+            + 0.0721750 * color.b.max(0.0).powf(APCA_MAIN_TRC);
+        // Soft clamps near black, where screens and eyes lose contrast
+        if y < APCA_BLACK_THRESHOLD {
+            y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+        } else {
+            y
+        }
+    }
+    let text_y = screen_luminance(text);
+    let bg_y = screen_luminance(bg);
+    if (bg_y - text_y).abs() < APCA_DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let lc = if bg_y > text_y {
+        let sapc = (bg_y.powf(APCA_NORM_BG) - text_y.powf(APCA_NORM_TXT)) * APCA_SCALE;
+        if sapc < APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc - APCA_LOW_OFFSET
+        }
+    } else {
+        let sapc = (bg_y.powf(APCA_REV_BG) - text_y.powf(APCA_REV_TXT)) * APCA_SCALE;
+        if sapc > -APCA_LOW_CLIP {
+            0.0
+        } else {
+            sapc + APCA_LOW_OFFSET
+        }
+    };
+    lc * 100.0
+}
+
+
+/// How the contrast between text and background colors is measured
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ContrastMetric {
+    /// WCAG 2.x contrast ratio
+    #[default]
+    Wcag,
+    /// Absolute APCA lightness contrast, which better reflects how readable light text on dark
+    /// colors is
+    Apca,
+}
+
+impl ContrastMetric {
+    pub const ALL: [ContrastMetric; 2] = [ContrastMetric::Wcag, ContrastMetric::Apca];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContrastMetric::Wcag => "WCAG 2",
+            ContrastMetric::Apca => "APCA",
+        }
+    }
+
+    /// Contrast of text on the background, higher is more readable
+    pub fn contrast(&self, text: &Color, bg: &Color) -> f32 {
+        match self {
+            ContrastMetric::Wcag => contrast_ratio(text, bg),
+            ContrastMetric::Apca => apca_contrast(text, bg).abs(),
+        }
+    }
+
+    /// The contrast formatted for display, e.g. `4.5:1` or `Lc 75`
+    pub fn format(&self, contrast: f32) -> String {
+        match self {
+            ContrastMetric::Wcag => format!("{:.1}:1", contrast),
+            ContrastMetric::Apca => format!("Lc {:.0}", contrast),
+        }
+    }
+}
+
+
+/// A text color picked by `best_text_color()`, with the contrast it achieves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextColorChoice {
+    pub color: Color,
+    pub contrast: f32,
+}
+
+
+/// The candidate with the highest contrast on the background, or `None` without candidates
+pub fn best_text_color(
+    bg: &Color,
+    candidates: &[Color],
+    metric: ContrastMetric,
+) -> Option<TextColorChoice> {
+    best_text_color_on_all(&[*bg], candidates, metric)
+}
+
+
+/// The candidate whose lowest contrast on any of the backgrounds is the highest, for text
+/// drawn over several colors. The reported contrast is that lowest contrast. `None` without
+/// candidates or backgrounds.
+pub fn best_text_color_on_all(
+    backgrounds: &[Color],
+    candidates: &[Color],
+    metric: ContrastMetric,
+) -> Option<TextColorChoice> {
+    if backgrounds.is_empty() {
+        return None;
+    }
+    candidates
+        .iter()
+        .map(|candidate| TextColorChoice {
+            color: *candidate,
+            contrast: backgrounds
+                .iter()
+                .map(|bg| metric.contrast(candidate, bg))
+                .fold(f32::INFINITY, f32::min),
+        })
+        // The first candidate wins ties, so the order of candidates is a preference
+        .fold(None, |best: Option<TextColorChoice>, choice| match best {
+            Some(best) if best.contrast >= choice.contrast => Some(best),
+            _ => Some(choice),
+        })
+}
+
+
+/// The color seen where `top` is drawn with `alpha` over `bottom`
+pub fn composite(top: &Color, alpha: f32, bottom: &Color) -> Color {
+    Color::from_rgb(
+        bottom.r + (top.r - bottom.r) * alpha,
+        bottom.g + (top.g - bottom.g) * alpha,
+        bottom.b + (top.b - bottom.b) * alpha,
+    )
+}
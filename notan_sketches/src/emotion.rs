@@ -1,21 +1,176 @@
+pub mod analyzer;
+pub mod arc;
+pub mod mapping;
+
+use mapping::ColorMappingTable;
 use notan::log;
 use notan::prelude::*;
-use palette::{FromColor, Hsv, Mix, RgbHue, Srgb};
+use palette::{FromColor, Hsv, Mix, Oklab, Oklch, RgbHue, Srgb};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EmocatMeta {
-    pub paragraph: u8,
-    pub from_line: u8,
-    pub to_line: u8,
+    pub paragraph: u32,
+    pub from_line: u32,
+    pub to_line: u32,
     pub file: String,
 }
 
 
+/// The eight basic emotions of Plutchik's wheel, as reported by the `emocat` analyzers
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum PlutchikEmotion {
+    Fear,
+    Anger,
+    Anticipation,
+    Trust,
+    Surprise,
+    Sadness,
+    Disgust,
+    Joy,
+}
+
+impl PlutchikEmotion {
+    pub const ALL: [PlutchikEmotion; 8] = [
+        PlutchikEmotion::Fear,
+        PlutchikEmotion::Anger,
+        PlutchikEmotion::Anticipation,
+        PlutchikEmotion::Trust,
+        PlutchikEmotion::Surprise,
+        PlutchikEmotion::Sadness,
+        PlutchikEmotion::Disgust,
+        PlutchikEmotion::Joy,
+    ];
+
+    /// The marker used for this emotion in `emocat` output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlutchikEmotion::Fear => "fear",
+            PlutchikEmotion::Anger => "anger",
+            PlutchikEmotion::Anticipation => "anticipation",
+            PlutchikEmotion::Trust => "trust",
+            PlutchikEmotion::Surprise => "surprise",
+            PlutchikEmotion::Sadness => "sadness",
+            PlutchikEmotion::Disgust => "disgust",
+            PlutchikEmotion::Joy => "joy",
+        }
+    }
+}
+
+impl fmt::Display for PlutchikEmotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PlutchikEmotion {
+    type Err = ParseMarkerError;
+
+    fn from_str(marker: &str) -> Result<Self, Self::Err> {
+        PlutchikEmotion::ALL
+            .iter()
+            .find(|emotion| emotion.as_str() == marker)
+            .copied()
+            .ok_or_else(|| ParseMarkerError(marker.to_string()))
+    }
+}
+
+impl TryFrom<String> for PlutchikEmotion {
+    type Error = ParseMarkerError;
+
+    fn try_from(marker: String) -> Result<Self, Self::Error> {
+        marker.parse()
+    }
+}
+
+impl From<PlutchikEmotion> for String {
+    fn from(emotion: PlutchikEmotion) -> Self {
+        emotion.as_str().to_string()
+    }
+}
+
+
+/// A marker in an analyzer's score list. Alongside the emotions, `emocat` reports the
+/// overall positive and negative sentiment in the same list.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum EmocatMarker {
+    Emotion(PlutchikEmotion),
+    Positive,
+    Negative,
+}
+
+impl EmocatMarker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmocatMarker::Emotion(emotion) => emotion.as_str(),
+            EmocatMarker::Positive => "positive",
+            EmocatMarker::Negative => "negative",
+        }
+    }
+}
+
+impl fmt::Display for EmocatMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmocatMarker {
+    type Err = ParseMarkerError;
+
+    fn from_str(marker: &str) -> Result<Self, Self::Err> {
+        match marker {
+            "positive" => Ok(EmocatMarker::Positive),
+            "negative" => Ok(EmocatMarker::Negative),
+            _ => Ok(EmocatMarker::Emotion(marker.parse()?)),
+        }
+    }
+}
+
+impl TryFrom<String> for EmocatMarker {
+    type Error = ParseMarkerError;
+
+    fn try_from(marker: String) -> Result<Self, Self::Error> {
+        marker.parse()
+    }
+}
+
+impl From<EmocatMarker> for String {
+    fn from(marker: EmocatMarker) -> Self {
+        marker.as_str().to_string()
+    }
+}
+
+
+/// Returned when an analyzer reports a marker that is not part of the emotion vocabulary
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseMarkerError(pub String);
+
+impl fmt::Display for ParseMarkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown emotion marker `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseMarkerError {}
+
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EmocatAnalyzerScore {
-    pub marker: String,
+    pub marker: EmocatMarker,
+    pub score: f32,
+}
+
+
+/// The score of a single emotion, without the sentiment markers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmotionScore {
+    pub emotion: PlutchikEmotion,
     pub score: f32,
 }
 
@@ -42,6 +197,123 @@ pub struct EmocatAnalyzerResults {
     pub t2e_demo: Vec<EmocatAnalyzerScore>,
 }
 
+impl EmocatAnalyzerResults {
+    /// Returns the scores reported by the selected analyzer, or the combined scores of all
+    /// analyzers for `AnalyzerSource::Ensemble`.
+    pub fn scores_for(&self, source: AnalyzerSource) -> Vec<EmocatAnalyzerScore> {
+        match source {
+            AnalyzerSource::Nrclex => self.nrclex.clone(),
+            AnalyzerSource::T2eRepo => self.t2e_repo.clone(),
+            AnalyzerSource::T2eDemo => self.t2e_demo.clone(),
+            AnalyzerSource::Ensemble => self.ensemble_scores(),
+        }
+    }
+
+    /// Each analyzer's scores are normalized so that analyzers using different scales weigh the
+    /// same, and then averaged per marker. Emotions are normalized by the analyzer's emotion
+    /// total and sentiments by its sentiment total, so that the emotions of an analyzer that
+    /// reports sentiment, like nrclex, aren't diluted next to one that doesn't.
+    ///
+    /// Emotions and sentiments are averaged separately, each only across the analyzers that
+    /// reported anything for them. The t2e analyzers, for example, always report zero
+    /// sentiment, and would otherwise just water down the nrclex sentiment.
+    fn ensemble_scores(&self) -> Vec<EmocatAnalyzerScore> {
+        let mut totals: Vec<(EmocatMarker, f32)> = vec![];
+        let mut emotion_analyzers = 0;
+        let mut sentiment_analyzers = 0;
+        let is_emotion = |marker: EmocatMarker| matches!(marker, EmocatMarker::Emotion(_));
+        let reported = |total: f32| total.is_finite() && total > 0.0;
+
+        for scores in [&self.nrclex, &self.t2e_repo, &self.t2e_demo] {
+            let total_of = |emotions: bool| -> f32 {
+                scores
+                    .iter()
+                    .filter(|s| is_emotion(s.marker) == emotions)
+                    .map(|s| s.score)
+                    .sum()
+            };
+            let emotion_total = total_of(true);
+            let sentiment_total = total_of(false);
+            if !reported(emotion_total) && !reported(sentiment_total) {
+                continue;
+            }
+            if reported(emotion_total) {
+                emotion_analyzers += 1;
+            }
+            if reported(sentiment_total) {
+                sentiment_analyzers += 1;
+            }
+            for score in scores.iter() {
+                let total = if is_emotion(score.marker) {
+                    emotion_total
+                } else {
+                    sentiment_total
+                };
+                let normalized = if reported(total) {
+                    score.score / total
+                } else {
+                    0.0
+                };
+                match totals
+                    .iter_mut()
+                    .find(|(marker, _)| *marker == score.marker)
+                {
+                    Some((_, sum)) => *sum += normalized,
+                    None => totals.push((score.marker, normalized)),
+                }
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(marker, sum)| {
+                let analyzers = match marker {
+                    EmocatMarker::Emotion(_) => emotion_analyzers,
+                    _ => sentiment_analyzers,
+                };
+                EmocatAnalyzerScore {
+                    marker,
+                    score: if analyzers > 0 {
+                        sum / analyzers as f32
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+
+/// Which of the `emocat` analyzers to base a model on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnalyzerSource {
+    #[default]
+    Nrclex,
+    T2eRepo,
+    T2eDemo,
+    /// Normalized average of all the analyzers
+    Ensemble,
+}
+
+impl AnalyzerSource {
+    pub const ALL: [AnalyzerSource; 4] = [
+        AnalyzerSource::Nrclex,
+        AnalyzerSource::T2eRepo,
+        AnalyzerSource::T2eDemo,
+        AnalyzerSource::Ensemble,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalyzerSource::Nrclex => "nrclex",
+            AnalyzerSource::T2eRepo => "t2e_repo",
+            AnalyzerSource::T2eDemo => "t2e_demo",
+            AnalyzerSource::Ensemble => "Ensemble",
+        }
+    }
+}
+
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EmocatTextAnalysis {
@@ -61,13 +333,15 @@ pub struct EmocatOutputDoc {
 
 
 pub struct EmoColor {
-    pub emotion: String,
+    /// `None` for the neutral color used when no emotion was detected
+    pub emotion: Option<PlutchikEmotion>,
     pub sentiment: Sentiment,
     pub hsv: Hsv,
 }
 
 
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum Sentiment {
     POSITIVE,
     NEGATIVE,
@@ -75,36 +349,38 @@ pub enum Sentiment {
 }
 
 
-pub fn get_emotion_sentiment(emotion: &str) -> Sentiment {
+pub fn get_emotion_sentiment(emotion: PlutchikEmotion) -> Sentiment {
     match emotion {
-        "fear" => Sentiment::NEGATIVE,
-        "anger" => Sentiment::NEGATIVE,
-        "anticipation" => Sentiment::NEUTRAL,
-        "trust" => Sentiment::POSITIVE,
-        "surprise" => Sentiment::NEUTRAL,
-        "sadness" => Sentiment::NEGATIVE,
-        "disgust" => Sentiment::NEGATIVE,
-        "joy" => Sentiment::POSITIVE,
-        _ => Sentiment::NEUTRAL,
+        PlutchikEmotion::Fear => Sentiment::NEGATIVE,
+        PlutchikEmotion::Anger => Sentiment::NEGATIVE,
+        PlutchikEmotion::Anticipation => Sentiment::NEUTRAL,
+        PlutchikEmotion::Trust => Sentiment::POSITIVE,
+        PlutchikEmotion::Surprise => Sentiment::NEUTRAL,
+        PlutchikEmotion::Sadness => Sentiment::NEGATIVE,
+        PlutchikEmotion::Disgust => Sentiment::NEGATIVE,
+        PlutchikEmotion::Joy => Sentiment::POSITIVE,
     }
 }
 
 
+/// Neutral gray used when there is no emotion to map a color from
+pub fn get_neutral_color() -> Hsv {
+    Hsv::new(RgbHue::from_degrees(180.0), 0.0, 0.50)
+}
+
+
 /// Returns color mapped to the emotion provided based on Plutchik color wheel here:
 /// http://shelleycrick.com/how-color-affects-emotions/
-pub fn get_mapped_color_plutchik(emotion: &str) -> Hsv {
+pub fn get_mapped_color_plutchik(emotion: PlutchikEmotion) -> Hsv {
     match emotion {
-        "fear" => Hsv::new(RgbHue::from_degrees(88.0), 1.0, 0.59),
-        "anger" => Hsv::new(RgbHue::from_degrees(350.0), 1.0, 0.72),
-        "anticipation" => Hsv::new(RgbHue::from_degrees(21.0), 1.0, 0.96),
-        "trust" => Hsv::new(RgbHue::from_degrees(69.0), 1.0, 0.72),
-        "surprise" => Hsv::new(RgbHue::from_degrees(136.0), 0.98, 0.50),
-        "sadness" => Hsv::new(RgbHue::from_degrees(206.0), 1.0, 0.85),
-        "disgust" => Hsv::new(RgbHue::from_degrees(300.0), 1.0, 0.24),
-        "joy" => Hsv::new(RgbHue::from_degrees(55.0), 1.0, 0.91),
-        _ => Hsv::new(RgbHue::from_degrees(180.0), 0.0, 0.50),
-        // _ => Hsv::new(RgbHue::from_degrees(0.0), 0.0, 0.0),
-        // _ => Hsv::new(RgbHue::from_degrees(0.0), 0.0, 1.0),
+        PlutchikEmotion::Fear => Hsv::new(RgbHue::from_degrees(88.0), 1.0, 0.59),
+        PlutchikEmotion::Anger => Hsv::new(RgbHue::from_degrees(350.0), 1.0, 0.72),
+        PlutchikEmotion::Anticipation => Hsv::new(RgbHue::from_degrees(21.0), 1.0, 0.96),
+        PlutchikEmotion::Trust => Hsv::new(RgbHue::from_degrees(69.0), 1.0, 0.72),
+        PlutchikEmotion::Surprise => Hsv::new(RgbHue::from_degrees(136.0), 0.98, 0.50),
+        PlutchikEmotion::Sadness => Hsv::new(RgbHue::from_degrees(206.0), 1.0, 0.85),
+        PlutchikEmotion::Disgust => Hsv::new(RgbHue::from_degrees(300.0), 1.0, 0.24),
+        PlutchikEmotion::Joy => Hsv::new(RgbHue::from_degrees(55.0), 1.0, 0.91),
     }
 }
 
@@ -117,123 +393,180 @@ pub fn get_mapped_color_plutchik(emotion: &str) -> Hsv {
 /// "for kicks" and to solidify the design of these mappings.
 ///
 /// http://www.arttherapyblog.com/online/color-meanings-symbolism
-pub fn get_mapped_color_therapy(emotion: &str) -> Hsv {
+pub fn get_mapped_color_therapy(emotion: PlutchikEmotion) -> Hsv {
     match emotion {
-        "fear" => Hsv::new(RgbHue::from_degrees(60.0), 0.8, 1.0),
-        "anger" => Hsv::new(RgbHue::from_degrees(5.0), 0.93, 1.0),
+        PlutchikEmotion::Fear => Hsv::new(RgbHue::from_degrees(60.0), 0.8, 1.0),
+        PlutchikEmotion::Anger => Hsv::new(RgbHue::from_degrees(5.0), 0.93, 1.0),
         // Loosely interpreting anticipation to be green
-        "anticipation" => Hsv::new(RgbHue::from_degrees(95.0), 0.72, 0.69),
-        "trust" => Hsv::new(RgbHue::from_degrees(224.0), 0.99, 1.0),
+        PlutchikEmotion::Anticipation => Hsv::new(RgbHue::from_degrees(95.0), 0.72, 0.69),
+        PlutchikEmotion::Trust => Hsv::new(RgbHue::from_degrees(224.0), 0.99, 1.0),
         // Loosely interpreting surprise as violet
-        "surprise" => Hsv::new(RgbHue::from_degrees(286.0), 0.99, 0.69),
-        "sadness" => Hsv::new(RgbHue::from_degrees(224.0), 0.99, 1.0),
+        PlutchikEmotion::Surprise => Hsv::new(RgbHue::from_degrees(286.0), 0.99, 0.69),
+        PlutchikEmotion::Sadness => Hsv::new(RgbHue::from_degrees(224.0), 0.99, 1.0),
         // Cannot find an equivalent, so just going to return gray
-        "disgust" => Hsv::new(RgbHue::from_degrees(180.0), 0.0, 0.50),
-        "joy" => Hsv::new(RgbHue::from_degrees(36.0), 0.99, 0.98),
-        _ => Hsv::new(RgbHue::from_degrees(180.0), 0.0, 0.50),
-        // _ => Hsv::new(RgbHue::from_degrees(0.0), 0.0, 0.0),
-        // _ => Hsv::new(RgbHue::from_degrees(0.0), 0.0, 1.0),
+        PlutchikEmotion::Disgust => get_neutral_color(),
+        PlutchikEmotion::Joy => Hsv::new(RgbHue::from_degrees(36.0), 0.99, 0.98),
     }
 }
 
+#[derive(Clone)]
 pub enum ColorMapping {
     PLUTCHIK,
     THERAPY,
+    /// A user-defined mapping, see `mapping::ColorMappingTable`
+    Custom(ColorMappingTable),
 }
 
 
 /// Returns colors & sentiment mapped to the emotion provided
-pub fn get_mapped_emocolor(emotion: &str, color_mapping: &ColorMapping) -> EmoColor {
+pub fn get_mapped_emocolor(emotion: PlutchikEmotion, color_mapping: &ColorMapping) -> EmoColor {
     let mapping_func = match color_mapping {
         ColorMapping::THERAPY => get_mapped_color_therapy,
+        ColorMapping::Custom(table) => return table.get_emocolor(emotion),
         _ => get_mapped_color_plutchik,
     };
-    match emotion {
-        "fear" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEGATIVE,
-            hsv: mapping_func(emotion),
-        },
-        "anger" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEGATIVE,
-            hsv: mapping_func(emotion),
-        },
-        "anticipation" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEUTRAL,
-            hsv: mapping_func(emotion),
-        },
-        "trust" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::POSITIVE,
-            hsv: mapping_func(emotion),
-        },
-        "surprise" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEUTRAL,
-            hsv: mapping_func(emotion),
-        },
-        "sadness" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEGATIVE,
-            hsv: mapping_func(emotion),
-        },
-        "disgust" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEGATIVE,
-            hsv: mapping_func(emotion),
-        },
-        "joy" => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::POSITIVE,
-            hsv: mapping_func(emotion),
-        },
-        _ => EmoColor {
-            emotion: emotion.to_string(),
-            sentiment: Sentiment::NEUTRAL,
-            hsv: mapping_func(emotion),
-        },
+    EmoColor {
+        emotion: Some(emotion),
+        sentiment: get_emotion_sentiment(emotion),
+        hsv: mapping_func(emotion),
+    }
+}
+
+
+/// Returns the neutral EmoColor used when no emotion was detected
+pub fn get_neutral_emocolor() -> EmoColor {
+    EmoColor {
+        emotion: None,
+        sentiment: Sentiment::NEUTRAL,
+        hsv: get_neutral_color(),
+    }
+}
+
+
+/// Reasons a `TopEmotionsModel` cannot be built from an analysis
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmotionModelError {
+    /// The analyzer output is missing the `positive` or `negative` sentiment marker
+    MissingSentiment(EmocatMarker),
+    /// The analyzer output has no emotion scores at all
+    EmptyScores,
+    /// A score is NaN or infinite
+    InvalidScore { marker: EmocatMarker, score: f32 },
+}
+
+impl fmt::Display for EmotionModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmotionModelError::MissingSentiment(marker) => {
+                write!(f, "analysis is missing the `{}` sentiment score", marker)
+            }
+            EmotionModelError::EmptyScores => write!(f, "analysis has no emotion scores"),
+            EmotionModelError::InvalidScore { marker, score } => {
+                write!(f, "analysis has an invalid `{}` score: {}", marker, score)
+            }
+        }
     }
 }
 
+impl std::error::Error for EmotionModelError {}
+
+
+/// How the colors of several emotions are combined into one
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BlendStrategy {
+    /// Average of all scoring emotions, weighted by score
+    Weighted,
+    /// Like `Weighted`, but only the `k` highest scoring emotions
+    TopK(usize),
+    /// Like `Weighted`, but desaturated the more evenly the scores are spread, so that
+    /// paragraphs without a clear emotion get a muted color
+    EntropyAware,
+}
+
+
+/// How much chroma is taken away from a blend with maximum entropy (all emotions scoring the
+/// same) when using `BlendStrategy::EntropyAware`
+const ENTROPY_DESATURATION: f32 = 0.8;
+
 
 pub struct TopEmotionsModel {
     pub positive: f32,
     pub negative: f32,
-    pub top_emotions: Vec<EmocatAnalyzerScore>,
+    pub top_emotions: Vec<EmotionScore>,
+    /// All emotion scores, highest first
+    pub scores: Vec<EmotionScore>,
 }
 
 impl TopEmotionsModel {
+    /// Panics if the analysis cannot be modeled. Prefer `try_from_analysis()` for documents
+    /// that haven't been checked beforehand.
     pub fn from_analysis(analysis: &EmocatTextAnalysis) -> Self {
-        let mut scores = analysis.results.nrclex.clone();
-        // log::debug!("Scores before {:?}", scores);
+        Self::try_from_analysis(analysis).unwrap()
+    }
 
-        let positive_pos = scores.iter().position(|s| s.marker == "positive").unwrap();
-        let positive_sentiment = scores.remove(positive_pos);
-        let negative_pos = scores.iter().position(|s| s.marker == "negative").unwrap();
-        let negative_sentiment = scores.remove(negative_pos);
-        // log::debug!(
-        //     "positive: {}, negative: {}",
-        //     positive_sentiment.score,
-        //     negative_sentiment.score
-        // );
-
-        scores.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
-        // log::debug!("Score after {:?}", scores);
+    /// Builds the model from the nrclex scores of the analysis
+    pub fn try_from_analysis(analysis: &EmocatTextAnalysis) -> Result<Self, EmotionModelError> {
+        Self::try_from_scores(&analysis.results.nrclex)
+    }
 
-        let mut top_emotions: Vec<EmocatAnalyzerScore> = Vec::new();
-        top_emotions.push(scores[0].clone());
-        for score in scores.iter().skip(1) {
-            if score.score == top_emotions[0].score {
-                top_emotions.push(score.clone());
-            }
+    /// Builds the model from the scores of the selected analyzer
+    pub fn try_from_source(
+        analysis: &EmocatTextAnalysis,
+        source: AnalyzerSource,
+    ) -> Result<Self, EmotionModelError> {
+        Self::try_from_scores(&analysis.results.scores_for(source))
+    }
+
+    pub fn try_from_scores(scores: &[EmocatAnalyzerScore]) -> Result<Self, EmotionModelError> {
+        // log::debug!("Scores before {:?}", scores);
+
+        if let Some(invalid) = scores.iter().find(|s| !s.score.is_finite()) {
+            return Err(EmotionModelError::InvalidScore {
+                marker: invalid.marker,
+                score: invalid.score,
+            });
         }
-        Self {
-            positive: positive_sentiment.score,
-            negative: negative_sentiment.score,
-            top_emotions: top_emotions,
+
+        let get_sentiment = |marker: EmocatMarker| {
+            scores
+                .iter()
+                .find(|s| s.marker == marker)
+                .map(|s| s.score)
+                .ok_or(EmotionModelError::MissingSentiment(marker))
+        };
+        let positive = get_sentiment(EmocatMarker::Positive)?;
+        let negative = get_sentiment(EmocatMarker::Negative)?;
+        // log::debug!("positive: {}, negative: {}", positive, negative);
+
+        let mut scores: Vec<EmotionScore> = scores
+            .iter()
+            .filter_map(|s| match s.marker {
+                EmocatMarker::Emotion(emotion) => Some(EmotionScore {
+                    emotion,
+                    score: s.score,
+                }),
+                _ => None,
+            })
+            .collect();
+        if scores.is_empty() {
+            return Err(EmotionModelError::EmptyScores);
         }
+        // Scores were checked to be finite above
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        // log::debug!("Score after {:?}", scores);
+
+        let top_score = scores[0].score;
+        let top_emotions: Vec<EmotionScore> = scores
+            .iter()
+            .filter(|s| s.score == top_score)
+            .copied()
+            .collect();
+        Ok(Self {
+            positive,
+            negative,
+            top_emotions,
+            scores,
+        })
     }
 
 
@@ -265,22 +598,21 @@ impl TopEmotionsModel {
         if top_emotions[0].score > 0.0 {
             return top_emotions
                 .iter()
-                .map(|s| get_mapped_emocolor(&s.marker, &color_mapping))
+                .map(|s| get_mapped_emocolor(s.emotion, color_mapping))
                 .collect();
         }
-        vec![get_mapped_emocolor("", &color_mapping)]
+        vec![get_neutral_emocolor()]
     }
 
-    pub fn get_simple_color(&self) -> Color {
+    pub fn get_simple_color(&self, color_mapping: &ColorMapping) -> Color {
         let top_emotions = &self.top_emotions;
         if top_emotions[0].score > 0.0 {
             // log::debug!("Top emotions: {:?}:", top_emotions);
 
-            let color_mapping = ColorMapping::PLUTCHIK;
-            let emocolors: Vec<EmoColor> = self.get_top_emocolors(&color_mapping);
+            let emocolors: Vec<EmoColor> = self.get_top_emocolors(color_mapping);
             // Start with a neutral gray
             if emocolors.len() > 1 {
-                let mut final_color = get_mapped_emocolor("", &color_mapping).hsv;
+                let mut final_color = get_neutral_color();
                 for emocolor in emocolors.iter() {
                     log::debug!("Before mix: {:?}", final_color);
                     let sentiment_value: f32 = match &emocolor.sentiment {
@@ -310,4 +642,69 @@ impl TopEmotionsModel {
         }
         Color::GRAY
     }
+
+    /// Normalized entropy of the emotion scores, from 0.0 (a single emotion) to 1.0 (every
+    /// emotion scoring the same)
+    pub fn get_entropy(&self) -> f32 {
+        let total: f32 = self.scores.iter().map(|s| s.score.max(0.0)).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let entropy: f32 = self
+            .scores
+            .iter()
+            .map(|s| s.score.max(0.0) / total)
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.ln())
+            .sum();
+        entropy / (PlutchikEmotion::ALL.len() as f32).ln()
+    }
+
+    /// Blends the colors of the scoring emotions in Oklab, so that the result reflects all of
+    /// them rather than only the ones tied at the top.
+    pub fn get_blended_color(
+        &self,
+        color_mapping: &ColorMapping,
+        strategy: BlendStrategy,
+    ) -> Color {
+        let take = match strategy {
+            BlendStrategy::TopK(k) => k,
+            _ => self.scores.len(),
+        };
+        let weighted: Vec<(Oklab, f32)> = self
+            .scores
+            .iter()
+            .take(take)
+            .filter(|s| s.score > 0.0)
+            .map(|s| {
+                let hsv = get_mapped_emocolor(s.emotion, color_mapping).hsv;
+                (Oklab::from_color(hsv), s.score)
+            })
+            .collect();
+        let total: f32 = weighted.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Color::GRAY;
+        }
+
+        let mut blended = Oklab::new(0.0, 0.0, 0.0);
+        for (color, weight) in weighted.iter() {
+            let weight = weight / total;
+            blended.l += color.l * weight;
+            blended.a += color.a * weight;
+            blended.b += color.b * weight;
+        }
+
+        if strategy == BlendStrategy::EntropyAware {
+            let mut lch = Oklch::from_color(blended);
+            lch.chroma *= 1.0 - self.get_entropy() * ENTROPY_DESATURATION;
+            blended = Oklab::from_color(lch);
+        }
+
+        let color = Srgb::from_color(blended);
+        Color::from_rgb(
+            color.red.clamp(0.0, 1.0),
+            color.green.clamp(0.0, 1.0),
+            color.blue.clamp(0.0, 1.0),
+        )
+    }
 }
@@ -0,0 +1,265 @@
+use super::{
+    EmocatAnalyzerResults, EmocatAnalyzerScore, EmocatMarker, EmocatMeta, EmocatOutputDoc,
+    EmocatTextAnalysis, PlutchikEmotion,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+
+const BUNDLED_LEXICON: &str = include_str!("../../examples/assets/lexicon/emotion_lexicon.txt");
+
+/// Markers in the order `emocat` reports them for nrclex
+const NRCLEX_MARKERS: [EmocatMarker; 10] = [
+    EmocatMarker::Emotion(PlutchikEmotion::Fear),
+    EmocatMarker::Emotion(PlutchikEmotion::Anger),
+    EmocatMarker::Emotion(PlutchikEmotion::Anticipation),
+    EmocatMarker::Emotion(PlutchikEmotion::Trust),
+    EmocatMarker::Emotion(PlutchikEmotion::Surprise),
+    EmocatMarker::Positive,
+    EmocatMarker::Negative,
+    EmocatMarker::Emotion(PlutchikEmotion::Sadness),
+    EmocatMarker::Emotion(PlutchikEmotion::Disgust),
+    EmocatMarker::Emotion(PlutchikEmotion::Joy),
+];
+
+
+/// Word to affect associations, read from files in the NRC Emotion Lexicon format: one
+/// `word<TAB>affect<TAB>association` line per word and affect, where the association is 0 or 1.
+/// Blank lines and lines starting with `#` are skipped.
+pub struct Lexicon {
+    words: HashMap<String, Vec<EmocatMarker>>,
+}
+
+impl Default for Lexicon {
+    /// The small lexicon bundled with the crate
+    fn default() -> Self {
+        Self::from_nrc_str(BUNDLED_LEXICON).expect("Bundled lexicon is invalid")
+    }
+}
+
+impl Lexicon {
+    pub fn from_nrc_str(source: &str) -> Result<Self, LexiconError> {
+        let mut words: HashMap<String, Vec<EmocatMarker>> = HashMap::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line_number = i + 1;
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 3 {
+                return Err(LexiconError::InvalidLine(line_number));
+            }
+            // The NRC lexicon abbreviates anticipation in some releases
+            let affect = match fields[1] {
+                "anticip" => "anticipation",
+                affect => affect,
+            };
+            let marker: EmocatMarker = affect.parse().map_err(|_| LexiconError::UnknownAffect {
+                line: line_number,
+                affect: fields[1].to_string(),
+            })?;
+            match fields[2] {
+                "1" => words
+                    .entry(fields[0].to_lowercase())
+                    .or_default()
+                    .push(marker),
+                "0" => {}
+                _ => return Err(LexiconError::InvalidLine(line_number)),
+            }
+        }
+        Ok(Self { words })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Affects associated with the word. Falls back to the word without a possessive or
+    /// plural `s` when the word itself is not in the lexicon.
+    pub fn lookup(&self, word: &str) -> &[EmocatMarker] {
+        let word = word.to_lowercase();
+        let candidates = [
+            Some(word.as_str()),
+            word.strip_suffix("'s"),
+            word.strip_suffix('s'),
+        ];
+        let markers = candidates
+            .into_iter()
+            .flatten()
+            .find_map(|w| self.words.get(w));
+        markers.map_or(&[], |markers| markers.as_slice())
+    }
+
+    /// Scores the text the way nrclex's `affect_frequencies` does: the number of times each
+    /// affect is associated with a word of the text, divided by the number of associations
+    /// found. Every marker is reported, with 0.0 for affects that were not found.
+    pub fn score(&self, text: &str) -> Vec<EmocatAnalyzerScore> {
+        let mut counts: HashMap<EmocatMarker, usize> = HashMap::new();
+        for word in words(text) {
+            for marker in self.lookup(&word) {
+                *counts.entry(*marker).or_default() += 1;
+            }
+        }
+        let total: usize = counts.values().sum();
+        NRCLEX_MARKERS
+            .iter()
+            .map(|marker| EmocatAnalyzerScore {
+                marker: *marker,
+                score: if total > 0 {
+                    counts.get(marker).copied().unwrap_or(0) as f32 / total as f32
+                } else {
+                    0.0
+                },
+            })
+            .collect()
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexiconError {
+    /// A line that is not `word<TAB>affect<TAB>0|1`
+    InvalidLine(usize),
+    UnknownAffect {
+        line: usize,
+        affect: String,
+    },
+}
+
+impl fmt::Display for LexiconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexiconError::InvalidLine(line) => write!(f, "invalid lexicon entry on line {}", line),
+            LexiconError::UnknownAffect { line, affect } => {
+                write!(f, "unknown affect `{}` on line {}", affect, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexiconError {}
+
+
+/// Lowercased words of the text. Apostrophes within words are kept, with typographic
+/// apostrophes normalized to `'`.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphabetic() || c == '\'' || c == '’'))
+        .map(|w| w.replace('’', "'").trim_matches('\'').to_lowercase())
+        .filter(|w| !w.is_empty())
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paragraph {
+    pub text: String,
+    /// First line of the paragraph in the source text, starting from 1
+    pub from_line: u32,
+    pub to_line: u32,
+}
+
+
+/// How text is divided into the paragraphs that are analyzed (and read) one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ParagraphSplit {
+    /// Paragraphs (or stanzas) separated by blank lines, like `emocat` does
+    #[default]
+    BlankLines,
+    /// Every non-blank line is a paragraph
+    Lines,
+    /// Groups of up to this many non-blank lines, for texts without blank lines between
+    /// paragraphs. Blank lines also end a group.
+    FixedLines(usize),
+}
+
+
+/// Splits the text into paragraphs (or stanzas) separated by blank lines, like `emocat` does.
+/// The lines of a paragraph are kept as they are, joined with `\n`.
+pub fn split_paragraphs(text: &str) -> Vec<Paragraph> {
+    split_paragraphs_by(text, ParagraphSplit::BlankLines)
+}
+
+
+pub fn split_paragraphs_by(text: &str, split: ParagraphSplit) -> Vec<Paragraph> {
+    let max_lines = match split {
+        ParagraphSplit::BlankLines => usize::MAX,
+        ParagraphSplit::Lines => 1,
+        ParagraphSplit::FixedLines(n) => n.max(1),
+    };
+    let mut paragraphs = vec![];
+    let mut lines: Vec<&str> = vec![];
+    let mut from_line = 0;
+    let mut flush = |lines: &mut Vec<&str>, from_line: u32| {
+        if !lines.is_empty() {
+            paragraphs.push(Paragraph {
+                text: lines.join("\n"),
+                from_line,
+                to_line: from_line + lines.len() as u32 - 1,
+            });
+            lines.clear();
+        }
+    };
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            flush(&mut lines, from_line);
+            continue;
+        }
+        if lines.is_empty() {
+            from_line = i as u32 + 1;
+        }
+        lines.push(line);
+        if lines.len() >= max_lines {
+            flush(&mut lines, from_line);
+        }
+    }
+    flush(&mut lines, from_line);
+    paragraphs
+}
+
+
+/// Produces `emocat` compatible analyses of plain text, without the Python toolchain.
+///
+/// Only the nrclex results are filled in, using the analyzer's `Lexicon`. The t2e results are
+/// left empty, so they should not be selected as the `AnalyzerSource` of a visualizer.
+#[derive(Default)]
+pub struct TextAnalyzer {
+    pub lexicon: Lexicon,
+    pub split: ParagraphSplit,
+}
+
+impl TextAnalyzer {
+    pub fn new(lexicon: Lexicon, split: ParagraphSplit) -> Self {
+        Self { lexicon, split }
+    }
+
+    /// `file` is only recorded in the meta of each analysis
+    pub fn analyze(&self, title: &str, author: &str, file: &str, text: &str) -> EmocatOutputDoc {
+        let analyses = split_paragraphs_by(text, self.split)
+            .into_iter()
+            .enumerate()
+            .map(|(i, paragraph)| EmocatTextAnalysis {
+                results: EmocatAnalyzerResults {
+                    nrclex: self.lexicon.score(&paragraph.text),
+                    t2e_repo: vec![],
+                    t2e_demo: vec![],
+                },
+                meta: EmocatMeta {
+                    paragraph: i as u32 + 1,
+                    from_line: paragraph.from_line,
+                    to_line: paragraph.to_line,
+                    file: file.to_string(),
+                },
+                text: paragraph.text,
+            })
+            .collect();
+        EmocatOutputDoc {
+            title: title.to_string(),
+            author: author.to_string(),
+            analyses,
+        }
+    }
+}
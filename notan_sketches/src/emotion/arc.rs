@@ -0,0 +1,190 @@
+use super::{
+    AnalyzerSource, BlendStrategy, ColorMapping, EmocatOutputDoc, EmotionModelError,
+    TopEmotionsModel,
+};
+use notan::prelude::*;
+
+
+/// Paragraphs on either side of a paragraph that are averaged into its smoothed values
+pub const DEFAULT_SMOOTHING_RADIUS: usize = 1;
+
+
+pub struct ArcPoint {
+    /// An error if the paragraph could not be analyzed. Such paragraphs count as neutral in
+    /// the arc, with zero valence and intensity.
+    pub model: Result<TopEmotionsModel, EmotionModelError>,
+    /// Positive minus negative sentiment
+    pub valence: f32,
+    /// Sum of all emotion scores
+    pub intensity: f32,
+    pub smoothed_valence: f32,
+    pub smoothed_intensity: f32,
+}
+
+
+/// The emotional arc of a whole document: per-paragraph scores, smoothed trends, peaks of
+/// emotional intensity and turning points where the sentiment changes direction.
+///
+/// Points are indexed like `EmocatOutputDoc::analyses`.
+pub struct EmotionArc {
+    points: Vec<ArcPoint>,
+    peaks: Vec<usize>,
+    turning_points: Vec<usize>,
+}
+
+impl EmotionArc {
+    pub fn from_doc(doc: &EmocatOutputDoc, source: AnalyzerSource) -> Self {
+        Self::from_doc_with_smoothing(doc, source, DEFAULT_SMOOTHING_RADIUS)
+    }
+
+    pub fn from_doc_with_smoothing(
+        doc: &EmocatOutputDoc,
+        source: AnalyzerSource,
+        smoothing_radius: usize,
+    ) -> Self {
+        let mut points: Vec<ArcPoint> = doc
+            .analyses
+            .iter()
+            .map(|analysis| {
+                let model = TopEmotionsModel::try_from_source(analysis, source);
+                let (valence, intensity) = match &model {
+                    Ok(model) => (
+                        model.positive - model.negative,
+                        model.scores.iter().map(|s| s.score).sum(),
+                    ),
+                    Err(_) => (0.0, 0.0),
+                };
+                ArcPoint {
+                    model,
+                    valence,
+                    intensity,
+                    smoothed_valence: valence,
+                    smoothed_intensity: intensity,
+                }
+            })
+            .collect();
+
+        let valences: Vec<f32> = points.iter().map(|p| p.valence).collect();
+        let intensities: Vec<f32> = points.iter().map(|p| p.intensity).collect();
+        let smoothed_valences = moving_average(&valences, smoothing_radius);
+        let smoothed_intensities = moving_average(&intensities, smoothing_radius);
+        for (i, point) in points.iter_mut().enumerate() {
+            point.smoothed_valence = smoothed_valences[i];
+            point.smoothed_intensity = smoothed_intensities[i];
+        }
+
+        let peaks = find_peaks(&smoothed_intensities);
+        let turning_points = find_turning_points(&smoothed_valences);
+        Self {
+            points,
+            peaks,
+            turning_points,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, paragraph: usize) -> Option<&ArcPoint> {
+        self.points.get(paragraph)
+    }
+
+    pub fn points(&self) -> &[ArcPoint] {
+        &self.points
+    }
+
+    /// Paragraphs where the smoothed intensity is at a local maximum
+    pub fn peaks(&self) -> &[usize] {
+        &self.peaks
+    }
+
+    /// Paragraphs where the smoothed valence changes from rising to falling or vice versa
+    pub fn turning_points(&self) -> &[usize] {
+        &self.turning_points
+    }
+
+    /// Direction of the smoothed valence at the paragraph: positive when the document is
+    /// becoming more positive, negative when it is becoming more negative
+    pub fn trend(&self, paragraph: usize) -> f32 {
+        if paragraph + 1 >= self.points.len() {
+            return 0.0;
+        }
+        self.points[paragraph + 1].smoothed_valence - self.points[paragraph].smoothed_valence
+    }
+
+    pub fn next_peak(&self, paragraph: usize) -> Option<usize> {
+        self.peaks.iter().copied().find(|p| *p > paragraph)
+    }
+
+    pub fn next_turning_point(&self, paragraph: usize) -> Option<usize> {
+        self.turning_points.iter().copied().find(|p| *p > paragraph)
+    }
+
+    /// The document's "emotional fingerprint": one blended color per paragraph, with
+    /// `Color::GRAY` for paragraphs that could not be analyzed
+    pub fn fingerprint(&self, color_mapping: &ColorMapping, strategy: BlendStrategy) -> Vec<Color> {
+        self.points
+            .iter()
+            .map(|point| match &point.model {
+                Ok(model) => model.get_blended_color(color_mapping, strategy),
+                Err(_) => Color::GRAY,
+            })
+            .collect()
+    }
+}
+
+
+/// Centered moving average, with the window shrinking at the ends
+fn moving_average(values: &[f32], radius: usize) -> Vec<f32> {
+    (0..values.len())
+        .map(|i| {
+            let from = i.saturating_sub(radius);
+            let to = (i + radius + 1).min(values.len());
+            let window = &values[from..to];
+            window.iter().sum::<f32>() / window.len() as f32
+        })
+        .collect()
+}
+
+
+fn find_peaks(values: &[f32]) -> Vec<usize> {
+    let mut peaks = vec![];
+    let mut i = 0;
+    while i < values.len() {
+        // A flat stretch is treated as a single point, reported at its start
+        let mut end = i;
+        while end + 1 < values.len() && values[end + 1] == values[i] {
+            end += 1;
+        }
+        let above_prev = i == 0 || values[i] > values[i - 1];
+        let above_next = end + 1 == values.len() || values[i] > values[end + 1];
+        let whole_arc = i == 0 && end + 1 == values.len();
+        if above_prev && above_next && !whole_arc {
+            peaks.push(i);
+        }
+        i = end + 1;
+    }
+    peaks
+}
+
+
+fn find_turning_points(values: &[f32]) -> Vec<usize> {
+    let mut turning_points = vec![];
+    let mut last_direction = 0.0;
+    for (i, pair) in values.windows(2).enumerate() {
+        if pair[1] == pair[0] {
+            continue;
+        }
+        let direction = (pair[1] - pair[0]).signum();
+        if last_direction != 0.0 && direction != last_direction {
+            turning_points.push(i);
+        }
+        last_direction = direction;
+    }
+    turning_points
+}
@@ -0,0 +1,188 @@
+use super::{ColorMapping, EmoColor, PlutchikEmotion, Sentiment};
+use palette::{Hsv, RgbHue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MappedEmotion {
+    /// In degrees
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    pub sentiment: Sentiment,
+}
+
+impl MappedEmotion {
+    pub fn hsv(&self) -> Hsv {
+        Hsv::new(RgbHue::from_degrees(self.hue), self.saturation, self.value)
+    }
+}
+
+
+/// A user-defined emotion to color mapping, loaded from a JSON or TOML file.
+///
+/// The file names the mapping and lists the HSV color and sentiment for every emotion in the
+/// analyzer vocabulary, e.g. in TOML:
+///
+/// ```toml
+/// name = "Pastel"
+///
+/// [emotions.joy]
+/// hue = 55.0
+/// saturation = 0.4
+/// value = 1.0
+/// sentiment = "positive"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColorMappingTable {
+    pub name: String,
+    pub emotions: HashMap<PlutchikEmotion, MappedEmotion>,
+}
+
+impl ColorMappingTable {
+    pub fn from_json_str(source: &str) -> Result<Self, ColorMappingError> {
+        let table: Self =
+            serde_json::from_str(source).map_err(|e| ColorMappingError::Parse(e.to_string()))?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn from_toml_str(source: &str) -> Result<Self, ColorMappingError> {
+        let table: Self =
+            toml::from_str(source).map_err(|e| ColorMappingError::Parse(e.to_string()))?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Parses the source as TOML or JSON depending on the extension of `file_name`
+    pub fn from_file_contents(file_name: &str, source: &str) -> Result<Self, ColorMappingError> {
+        if file_name.ends_with(".toml") {
+            Self::from_toml_str(source)
+        } else {
+            Self::from_json_str(source)
+        }
+    }
+
+    /// Loads a `.json` or `.toml` mapping file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: &str) -> Result<Self, ColorMappingError> {
+        let source =
+            std::fs::read_to_string(path).map_err(|e| ColorMappingError::Io(e.to_string()))?;
+        Self::from_file_contents(path, &source)
+    }
+
+    /// Checks that every emotion in the analyzer vocabulary is mapped, and that the colors
+    /// are within HSV range.
+    pub fn validate(&self) -> Result<(), ColorMappingError> {
+        let missing: Vec<PlutchikEmotion> = PlutchikEmotion::ALL
+            .into_iter()
+            .filter(|emotion| !self.emotions.contains_key(emotion))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ColorMappingError::MissingEmotions(missing));
+        }
+        for emotion in PlutchikEmotion::ALL {
+            let mapped = &self.emotions[&emotion];
+            let in_range = |v: f32| (0.0..=1.0).contains(&v);
+            if !mapped.hue.is_finite() || !in_range(mapped.saturation) || !in_range(mapped.value) {
+                return Err(ColorMappingError::OutOfRange(emotion));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_emocolor(&self, emotion: PlutchikEmotion) -> EmoColor {
+        // Tables are validated on load, so every emotion is present
+        let mapped = &self.emotions[&emotion];
+        EmoColor {
+            emotion: Some(emotion),
+            sentiment: mapped.sentiment,
+            hsv: mapped.hsv(),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorMappingError {
+    Io(String),
+    Parse(String),
+    MissingEmotions(Vec<PlutchikEmotion>),
+    /// Saturation or value outside of 0.0..=1.0, or a non-finite hue
+    OutOfRange(PlutchikEmotion),
+}
+
+impl fmt::Display for ColorMappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorMappingError::Io(err) => write!(f, "could not read color mapping: {}", err),
+            ColorMappingError::Parse(err) => write!(f, "could not parse color mapping: {}", err),
+            ColorMappingError::MissingEmotions(missing) => {
+                let names: Vec<&str> = missing.iter().map(|e| e.as_str()).collect();
+                write!(f, "color mapping is missing: {}", names.join(", "))
+            }
+            ColorMappingError::OutOfRange(emotion) => {
+                write!(f, "color mapping for `{}` is out of HSV range", emotion)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColorMappingError {}
+
+
+/// Color mappings available for selection, by name. Starts out with the built-in mappings.
+pub struct ColorMappingRegistry {
+    mappings: Vec<(String, ColorMapping)>,
+}
+
+impl Default for ColorMappingRegistry {
+    fn default() -> Self {
+        Self {
+            mappings: vec![
+                ("Plutchik".to_string(), ColorMapping::PLUTCHIK),
+                ("Art Therapy".to_string(), ColorMapping::THERAPY),
+            ],
+        }
+    }
+}
+
+impl ColorMappingRegistry {
+    /// Registers the table under its name, replacing any mapping already registered with
+    /// that name.
+    pub fn register(&mut self, table: ColorMappingTable) {
+        let name = table.name.clone();
+        let mapping = ColorMapping::Custom(table);
+        match self.mappings.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = mapping,
+            None => self.mappings.push((name, mapping)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ColorMapping> {
+        self.mappings
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, mapping)| mapping)
+    }
+
+    /// The user-defined tables, in the order they were first registered
+    pub fn tables(&self) -> Vec<&ColorMappingTable> {
+        self.mappings
+            .iter()
+            .filter_map(|(_, mapping)| match mapping {
+                ColorMapping::Custom(table) => Some(table),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.mappings
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
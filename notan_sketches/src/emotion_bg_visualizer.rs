@@ -1,3 +1,9 @@
+pub mod harness;
+pub mod library;
+pub mod options;
+pub mod presentation;
+pub mod read_along;
+pub mod settings;
 pub mod ui;
 pub mod visualizers;
 
@@ -0,0 +1,107 @@
+use super::visualizers::EmoVisualizer;
+use crate::emotion::EmocatTextAnalysis;
+use notan::math::{vec2, Vec2};
+use notan::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// Work size used when running visualizers headless, matching the default native window
+pub const HEADLESS_WORK_SIZE: Vec2 = vec2(1920.0, 1080.0);
+/// Seconds between frames when running visualizers headless, as if running at 60 fps
+pub const HEADLESS_FRAME_TIME: f32 = 1.0 / 60.0;
+
+
+/// The colors and layout a visualizer would draw at a given moment, for regression tests.
+/// Colors are recorded as `#rrggbbaa` hex strings so that snapshots diff cleanly.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VisualizerSnapshot {
+    pub bg_color: String,
+    pub target_color: String,
+    pub text_color: String,
+    /// Current colors of the tiles by row, for visualizers that draw tiles
+    pub tiles: Vec<Vec<String>>,
+    pub tile_size: [f32; 2],
+}
+
+impl VisualizerSnapshot {
+    pub fn new(bg_color: Color, target_color: Color, text_color: Color) -> Self {
+        Self {
+            bg_color: hex(bg_color),
+            target_color: hex(target_color),
+            text_color: hex(text_color),
+            tiles: vec![],
+            tile_size: [0.0, 0.0],
+        }
+    }
+}
+
+
+pub fn hex(color: Color) -> String {
+    let [r, g, b, a] = color.rgba_u8();
+    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+
+/// Drives the visualizer like the app does, without a window: `reset()`, then `update_model()`
+/// with the analysis, then `frames` frames of `update_visualization()` and `update_layout()`,
+/// each `HEADLESS_FRAME_TIME` seconds long.
+/// The visualizer is reseeded first so that the snapshot is the same on every run.
+pub fn run_headless(
+    visualizer: &mut dyn EmoVisualizer,
+    analysis: &EmocatTextAnalysis,
+    seed: u64,
+    frames: usize,
+) -> VisualizerSnapshot {
+    visualizer.reset(Color::WHITE, Color::BLACK, false);
+    visualizer.reseed(seed);
+    visualizer.update_model(analysis);
+    for _ in 0..frames {
+        visualizer.update_visualization(HEADLESS_FRAME_TIME);
+        visualizer.update_layout(HEADLESS_WORK_SIZE);
+    }
+    visualizer.snapshot()
+}
+
+
+/// Compares the snapshot with the one saved at `path`, panicking with the differing lines if
+/// they don't match, or if there is no saved snapshot. The snapshot is saved instead only when
+/// the `UPDATE_SNAPSHOTS` environment variable is set.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn assert_snapshot(path: impl AsRef<Path>, snapshot: &VisualizerSnapshot) {
+    let path = path.as_ref();
+    let actual = serde_json::to_string_pretty(snapshot).unwrap() + "\n";
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(err) => panic!(
+            "No snapshot at {} ({}). Run with UPDATE_SNAPSHOTS=1 to record it.",
+            path.display(),
+            err
+        ),
+    };
+    if expected != actual {
+        let diff: Vec<String> = expected
+            .lines()
+            .zip(actual.lines())
+            .enumerate()
+            .filter(|(_, (e, a))| e != a)
+            .map(|(i, (e, a))| format!("line {}:\n  - {}\n  + {}", i + 1, e.trim(), a.trim()))
+            .collect();
+        panic!(
+            "Snapshot {} changed ({} lines expected, {} found):\n{}\n\
+             Rerun with UPDATE_SNAPSHOTS=1 to accept the changes.",
+            path.display(),
+            expected.lines().count(),
+            actual.lines().count(),
+            diff.join("\n")
+        );
+    }
+}
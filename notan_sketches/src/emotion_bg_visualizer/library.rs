@@ -0,0 +1,303 @@
+use crate::emotion::analyzer::TextAnalyzer;
+use crate::emotion::mapping::{ColorMappingRegistry, ColorMappingTable};
+use crate::emotion::EmocatOutputDoc;
+use crate::persistence;
+use notan::log;
+use serde::de::DeserializeOwned;
+use std::fmt;
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, rc::Rc};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Key the user's documents are persisted under, see `crate::persistence`
+const LIBRARY_KEY: &str = "emo_bg_visualizer_library";
+/// Key the user's color mappings are persisted under
+const COLOR_MAPPINGS_KEY: &str = "emo_bg_visualizer_color_mappings";
+
+
+/// The documents available for reading: the bundled documents, followed by documents loaded
+/// by the user at runtime. User documents are saved whenever they change and restored in the
+/// next session.
+pub struct DocumentLibrary {
+    docs: Vec<EmocatOutputDoc>,
+    bundled: usize,
+}
+
+impl DocumentLibrary {
+    pub fn new(bundled: Vec<EmocatOutputDoc>) -> Self {
+        let bundled_len = bundled.len();
+        let mut docs = bundled;
+        docs.extend(load_saved::<EmocatOutputDoc>(LIBRARY_KEY, "document"));
+        Self {
+            docs,
+            bundled: bundled_len,
+        }
+    }
+
+    pub fn docs(&self) -> &[EmocatOutputDoc] {
+        &self.docs
+    }
+
+    pub fn is_user_doc(&self, index: usize) -> bool {
+        index >= self.bundled && index < self.docs.len()
+    }
+
+    /// Adds the document, replacing a user document with the same title and author, and
+    /// returns its index
+    pub fn add(&mut self, doc: EmocatOutputDoc) -> usize {
+        let existing = (self.bundled..self.docs.len())
+            .find(|i| self.docs[*i].title == doc.title && self.docs[*i].author == doc.author);
+        let index = match existing {
+            Some(i) => {
+                self.docs[i] = doc;
+                i
+            }
+            None => {
+                self.docs.push(doc);
+                self.docs.len() - 1
+            }
+        };
+        self.save();
+        index
+    }
+
+    /// Removes a user document. Bundled documents can't be removed.
+    pub fn remove(&mut self, index: usize) {
+        if self.is_user_doc(index) {
+            self.docs.remove(index);
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string(&self.docs[self.bundled..])
+            .map_err(|e| e.to_string())
+            .and_then(|json| persistence::save_string(LIBRARY_KEY, &json));
+        if let Err(err) = result {
+            log::error!("Could not save documents: {}", err);
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentError {
+    NotText {
+        file_name: String,
+    },
+    /// Malformed JSON, or JSON that is not an emocat document
+    Json {
+        file_name: String,
+        message: String,
+    },
+    NoParagraphs {
+        file_name: String,
+    },
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentError::NotText { file_name } => {
+                write!(f, "{} is not a text file", file_name)
+            }
+            DocumentError::Json { file_name, message } => {
+                write!(
+                    f,
+                    "{} is not a valid emocat document: {}",
+                    file_name, message
+                )
+            }
+            DocumentError::NoParagraphs { file_name } => {
+                write!(f, "{} has no paragraphs to read", file_name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+
+/// Parses a document loaded at runtime. `.txt` files are analyzed with the bundled lexicon,
+/// using the file name as the title; anything else is expected to be emocat JSON.
+pub fn parse_document(file_name: &str, data: &[u8]) -> Result<EmocatOutputDoc, DocumentError> {
+    let source = std::str::from_utf8(data).map_err(|_| DocumentError::NotText {
+        file_name: file_name.to_string(),
+    })?;
+    let doc = if file_name.ends_with(".txt") {
+        let title = file_name.trim_end_matches(".txt");
+        TextAnalyzer::default().analyze(title, "Unknown", file_name, source)
+    } else {
+        serde_json::from_str(source).map_err(|e| DocumentError::Json {
+            file_name: file_name.to_string(),
+            // Includes the line and column of the problem
+            message: e.to_string(),
+        })?
+    };
+    if doc.analyses.is_empty() {
+        return Err(DocumentError::NoParagraphs {
+            file_name: file_name.to_string(),
+        });
+    }
+    Ok(doc)
+}
+
+
+/// True if a file loaded at runtime is a color mapping rather than a document: a `.toml`
+/// file, or JSON with an `emotions` table (see `ColorMappingTable`).
+pub fn is_color_mapping_file(file_name: &str, data: &[u8]) -> bool {
+    if file_name.ends_with(".toml") {
+        return true;
+    }
+    if file_name.ends_with(".txt") {
+        return false;
+    }
+    match serde_json::from_slice::<serde_json::Value>(data) {
+        Ok(value) => value.get("emotions").is_some(),
+        Err(_) => false,
+    }
+}
+
+
+/// Restores the entries saved under `key` one at a time, so that an entry that doesn't parse
+/// anymore, such as one saved by an older version, is logged and skipped without losing the
+/// others
+fn load_saved<T: DeserializeOwned>(key: &str, entry_name: &str) -> Vec<T> {
+    let Some(saved) = persistence::load_string(key) else {
+        return vec![];
+    };
+    let entries = match serde_json::from_str::<Vec<serde_json::Value>>(&saved) {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::error!("Could not restore saved {}s: {}", entry_name, err);
+            return vec![];
+        }
+    };
+    entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, entry)| match serde_json::from_value(entry) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log::error!("Skipping saved {} {}: {}", entry_name, i + 1, err);
+                None
+            }
+        })
+        .collect()
+}
+
+
+/// Registers the color mappings the user loaded in previous sessions
+pub fn restore_color_mappings(registry: &mut ColorMappingRegistry) {
+    for table in load_saved::<ColorMappingTable>(COLOR_MAPPINGS_KEY, "color mapping") {
+        match table.validate() {
+            Ok(()) => registry.register(table),
+            Err(err) => log::error!("Skipping saved color mapping {}: {}", table.name, err),
+        }
+    }
+}
+
+
+/// Saves the user-defined color mappings, to be restored in the next session
+pub fn save_color_mappings(registry: &ColorMappingRegistry) {
+    let result = serde_json::to_string(&registry.tables())
+        .map_err(|e| e.to_string())
+        .and_then(|json| persistence::save_string(COLOR_MAPPINGS_KEY, &json));
+    if let Err(err) = result {
+        log::error!("Could not save color mappings: {}", err);
+    }
+}
+
+
+/// Files read in the browser, which arrive asynchronously
+#[cfg(target_arch = "wasm32")]
+type LoadedFiles = Rc<RefCell<Vec<(String, Vec<u8>)>>>;
+
+
+/// A hidden `<input type="file">` for picking documents and color mappings in the browser.
+/// Files picked with it, or passed to `read_file()` after being dropped on the page, are
+/// collected with `take_loaded()` once they have been read.
+#[cfg(target_arch = "wasm32")]
+pub struct FileInput {
+    input: web_sys::HtmlInputElement,
+    loaded: LoadedFiles,
+    _on_change: Closure<dyn FnMut()>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl FileInput {
+    pub fn new() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let input: web_sys::HtmlInputElement =
+            document.create_element("input").ok()?.dyn_into().ok()?;
+        input.set_type("file");
+        input.set_accept(".json,.txt,.toml");
+        input.set_multiple(true);
+        input.style().set_property("display", "none").ok()?;
+        document.body()?.append_child(&input).ok()?;
+
+        let loaded: LoadedFiles = Rc::new(RefCell::new(vec![]));
+        let on_change = {
+            let input = input.clone();
+            let loaded = loaded.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if let Some(files) = input.files() {
+                    for i in 0..files.length() {
+                        if let Some(file) = files.get(i) {
+                            read_into(file, loaded.clone());
+                        }
+                    }
+                }
+                // Allows picking the same file again
+                input.set_value("");
+            })
+        };
+        input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+
+        Some(Self {
+            input,
+            loaded,
+            _on_change: on_change,
+        })
+    }
+
+    /// Opens the browser's file picker
+    pub fn open(&self) {
+        self.input.click();
+    }
+
+    pub fn read_file(&self, file: web_sys::File) {
+        read_into(file, self.loaded.clone());
+    }
+
+    /// File names and contents of the files read since the last call
+    pub fn take_loaded(&self) -> Vec<(String, Vec<u8>)> {
+        self.loaded.borrow_mut().drain(..).collect()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_into(file: web_sys::File, loaded: LoadedFiles) {
+    let reader = match web_sys::FileReader::new() {
+        Ok(reader) => reader,
+        Err(err) => {
+            log::error!("Could not create a FileReader: {:?}", err);
+            return;
+        }
+    };
+    let name = file.name();
+    let on_load = {
+        let reader = reader.clone();
+        Closure::once_into_js(move || {
+            match reader.result().ok().and_then(|result| result.as_string()) {
+                Some(text) => loaded.borrow_mut().push((name, text.into_bytes())),
+                None => log::error!("Could not read {}", name),
+            }
+        })
+    };
+    reader.set_onload(Some(on_load.unchecked_ref()));
+    if let Err(err) = reader.read_as_text(&file) {
+        log::error!("Could not read {}: {:?}", file.name(), err);
+    }
+}
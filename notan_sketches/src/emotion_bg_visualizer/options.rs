@@ -0,0 +1,198 @@
+use notan::prelude::Color;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::ops::RangeInclusive;
+
+/// Saved values of a visualizer's options, keyed by `VisualizerOption::name`
+pub type OptionValues = Map<String, Value>;
+
+
+/// An enum whose variants are the choices of an option. Saved as the variant's serialized
+/// name, so renaming a variant needs a `#[serde(rename)]` to keep saved choices.
+pub trait OptionChoice: Serialize + DeserializeOwned + Copy + PartialEq + 'static {
+    fn all() -> &'static [Self];
+    fn label(&self) -> &'static str;
+}
+
+
+/// The field of a choice option, with the type of the choice erased so that options of
+/// different types can be listed together. Implemented for every `OptionChoice`.
+pub trait ChoiceField {
+    fn labels(&self) -> Vec<&'static str>;
+    fn selected(&self) -> usize;
+    fn select(&mut self, index: usize);
+    fn to_value(&self) -> Value;
+    /// False, leaving the field as it is, if `value` isn't one of the choices
+    fn set_value(&mut self, value: &Value) -> bool;
+}
+
+impl<T: OptionChoice> ChoiceField for T {
+    fn labels(&self) -> Vec<&'static str> {
+        T::all().iter().map(|choice| choice.label()).collect()
+    }
+
+    fn selected(&self) -> usize {
+        T::all()
+            .iter()
+            .position(|choice| choice == self)
+            .unwrap_or(0)
+    }
+
+    fn select(&mut self, index: usize) {
+        if let Some(choice) = T::all().get(index) {
+            *self = *choice;
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    fn set_value(&mut self, value: &Value) -> bool {
+        match serde_json::from_value(value.clone()) {
+            Ok(choice) => {
+                *self = choice;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+
+pub enum OptionKind<'a> {
+    /// One of the variants of an `OptionChoice` enum
+    Choice(&'a mut dyn ChoiceField),
+    Range {
+        value: &'a mut f32,
+        range: RangeInclusive<f32>,
+        /// Unit shown after the value, such as " s"
+        suffix: &'static str,
+    },
+    Toggle(&'a mut bool),
+    Color(&'a mut Color),
+}
+
+
+/// A user-configurable option of a visualizer, borrowing the field it sets. Visualizers list
+/// their options in `EmoVisualizer::options()`, which are then shown in the settings UI and
+/// saved between sessions without any code specific to the option.
+pub struct VisualizerOption<'a> {
+    /// Key the option is saved under. Renaming it loses the saved value.
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: OptionKind<'a>,
+}
+
+impl<'a> VisualizerOption<'a> {
+    pub fn choice(
+        name: &'static str,
+        label: &'static str,
+        field: &'a mut impl OptionChoice,
+    ) -> Self {
+        Self {
+            name,
+            label,
+            kind: OptionKind::Choice(field),
+        }
+    }
+
+    pub fn range(
+        name: &'static str,
+        label: &'static str,
+        value: &'a mut f32,
+        range: RangeInclusive<f32>,
+        suffix: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            label,
+            kind: OptionKind::Range {
+                value,
+                range,
+                suffix,
+            },
+        }
+    }
+
+    pub fn toggle(name: &'static str, label: &'static str, value: &'a mut bool) -> Self {
+        Self {
+            name,
+            label,
+            kind: OptionKind::Toggle(value),
+        }
+    }
+
+    pub fn color(name: &'static str, label: &'static str, value: &'a mut Color) -> Self {
+        Self {
+            name,
+            label,
+            kind: OptionKind::Color(value),
+        }
+    }
+
+    pub fn value(&self) -> Value {
+        match &self.kind {
+            OptionKind::Choice(field) => field.to_value(),
+            OptionKind::Range { value, .. } => json!(**value),
+            OptionKind::Toggle(value) => json!(**value),
+            OptionKind::Color(color) => json!([color.r, color.g, color.b, color.a]),
+        }
+    }
+
+    /// Sets the option from a saved value. Values of the wrong type are ignored and ranges
+    /// are clamped, so that settings saved for older versions of the option can't break it.
+    pub fn set_value(&mut self, saved: &Value) -> bool {
+        match &mut self.kind {
+            OptionKind::Choice(field) => field.set_value(saved),
+            OptionKind::Range { value, range, .. } => match saved.as_f64() {
+                Some(saved) => {
+                    **value = (saved as f32).clamp(*range.start(), *range.end());
+                    true
+                }
+                None => false,
+            },
+            OptionKind::Toggle(value) => match saved.as_bool() {
+                Some(saved) => {
+                    **value = saved;
+                    true
+                }
+                None => false,
+            },
+            OptionKind::Color(color) => {
+                let components: Option<Vec<f32>> = saved.as_array().map(|array| {
+                    array
+                        .iter()
+                        .filter_map(|component| component.as_f64())
+                        .map(|component| component as f32)
+                        .collect()
+                });
+                match components.as_deref() {
+                    Some(&[r, g, b, a]) => {
+                        **color = Color::new(r, g, b, a);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+
+pub fn save_options(options: &[VisualizerOption]) -> OptionValues {
+    options
+        .iter()
+        .map(|option| (option.name.to_string(), option.value()))
+        .collect()
+}
+
+/// Sets the options that have saved values, leaving the rest as they are
+pub fn restore_options(options: &mut [VisualizerOption], values: &OptionValues) {
+    for option in options.iter_mut() {
+        if let Some(value) = values.get(option.name) {
+            option.set_value(value);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+/// Reading pace presentations advance at by default, slower than silent reading so that
+/// passers-by can keep up
+pub const DEFAULT_PRESENTATION_WPM: f32 = 120.0;
+/// Seconds a page is shown for at least, such as a title or a very short paragraph
+pub const MIN_PAGE_SECONDS: f32 = 5.0;
+
+
+/// Seconds it takes to read `words` words at `wpm` words per minute, but no less than
+/// `MIN_PAGE_SECONDS`
+pub fn reading_time(words: usize, wpm: f32) -> f32 {
+    (words as f32 * 60.0 / wpm.max(1.0)).max(MIN_PAGE_SECONDS)
+}
+
+
+/// Times the pages of the reader in presentation mode, for unattended gallery or kiosk
+/// installations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Presentation {
+    /// Reading pace in words per minute
+    pub wpm: f32,
+    elapsed: f32,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRESENTATION_WPM)
+    }
+}
+
+impl Presentation {
+    pub fn new(wpm: f32) -> Self {
+        Self { wpm, elapsed: 0.0 }
+    }
+
+    /// Starts timing a page that was just turned to
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    /// Advances by `delta` seconds on a page with `words` words. Returns true when it's time
+    /// to turn the page: once the page has been shown for its reading time, and the
+    /// background has finished transitioning to the page's colors (`transition_complete`, see
+    /// `ColorTransition::is_complete()`).
+    pub fn step(&mut self, delta: f32, words: usize, transition_complete: bool) -> bool {
+        self.elapsed += delta;
+        if self.elapsed >= reading_time(words, self.wpm) && transition_complete {
+            self.restart();
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,88 @@
+use crate::emotion::analyzer::Lexicon;
+use crate::emotion::{BlendStrategy, ColorMapping, EmocatTextAnalysis, TopEmotionsModel};
+use notan::prelude::Color;
+use std::ops::Range;
+
+/// Reading pace the read-along highlight moves at
+pub const READ_ALONG_WPM: f32 = 160.0;
+/// Seconds a line with words is highlighted for at least, however short it is
+const MIN_LINE_SECONDS: f32 = 1.5;
+/// Highlight for lines without any emotion words in the lexicon
+const NEUTRAL_HIGHLIGHT_COLOR: Color = Color::GRAY;
+
+
+/// A line of a paragraph, as split by the analysis's `EmocatMeta` line range
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadAlongLine {
+    /// Line number in the source file
+    pub number: u32,
+    pub words: usize,
+    /// Blend of the line's own emotions, scored with the lexicon
+    pub color: Color,
+}
+
+impl ReadAlongLine {
+    /// Seconds it takes to read the line at `READ_ALONG_WPM`. Blank lines take no time.
+    pub fn reading_time(&self) -> f32 {
+        if self.words == 0 {
+            return 0.0;
+        }
+        (self.words as f32 * 60.0 / READ_ALONG_WPM).max(MIN_LINE_SECONDS)
+    }
+}
+
+
+/// Splits the analyzed paragraph into its lines, numbered from the analysis's `from_line`, and
+/// colors each one by the emotions the lexicon finds in it.
+pub fn read_along_lines(
+    analysis: &EmocatTextAnalysis,
+    lexicon: &Lexicon,
+    color_mapping: &ColorMapping,
+) -> Vec<ReadAlongLine> {
+    analysis
+        .text
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| ReadAlongLine {
+            number: analysis.meta.from_line + i as u32,
+            words: line.split_whitespace().count(),
+            color: match TopEmotionsModel::try_from_scores(&lexicon.score(line)) {
+                Ok(model) => model.get_blended_color(color_mapping, BlendStrategy::Weighted),
+                Err(_) => NEUTRAL_HIGHLIGHT_COLOR,
+            },
+        })
+        .collect()
+}
+
+
+/// The line being read along with, moving on to the next line once it has been highlighted
+/// for its reading time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReadAlongCursor {
+    pub line: usize,
+    elapsed: f32,
+}
+
+impl ReadAlongCursor {
+    pub fn restart(&mut self, line: usize) {
+        self.line = line;
+        self.elapsed = 0.0;
+    }
+
+    /// Advances by `delta` seconds through the `shown` lines, such as the lines on the current
+    /// page, stopping at the last of them. Jumps into `shown` if the cursor is outside it.
+    pub fn step(&mut self, delta: f32, lines: &[ReadAlongLine], shown: Range<usize>) {
+        let shown = shown.start..shown.end.min(lines.len());
+        if shown.is_empty() {
+            return;
+        }
+        if !shown.contains(&self.line) {
+            self.restart(shown.start);
+        }
+        self.elapsed += delta;
+        while self.line + 1 < shown.end && self.elapsed >= lines[self.line].reading_time() {
+            self.elapsed -= lines[self.line].reading_time();
+            self.line += 1;
+        }
+    }
+}
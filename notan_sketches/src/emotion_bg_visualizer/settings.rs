@@ -0,0 +1,113 @@
+use super::options::OptionValues;
+use super::visualizers::VisualizerSelection;
+use crate::emotion::EmocatOutputDoc;
+use crate::persistence;
+use notan::log;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Key the reader's settings are persisted under, see `crate::persistence`
+const SETTINGS_KEY: &str = "emo_bg_visualizer_settings";
+/// Version of the saved settings format. Adding settings doesn't need a new version, as
+/// settings missing from older saves keep their defaults; renaming or changing the meaning of
+/// one does.
+pub const SETTINGS_VERSION: u32 = 1;
+
+
+/// The reader's choices, saved between sessions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReaderSettings {
+    pub version: u32,
+    pub visualizer: VisualizerSelection,
+    /// Options of each visualizer that has been used, kept when switching between them
+    pub visualizer_options: HashMap<VisualizerSelection, OptionValues>,
+    pub dynamic_text_color: bool,
+    pub color_mapping: String,
+    pub presentation_wpm: f32,
+    /// Where the reader was when the session ended, if reading
+    pub last_read: Option<ReadPosition>,
+}
+
+impl ReaderSettings {
+    /// Restores the settings saved in a previous session on top of `defaults`, or `defaults`
+    /// if there are none or they can't be read
+    pub fn load(defaults: ReaderSettings) -> Self {
+        let Some(saved) = persistence::load_string(SETTINGS_KEY) else {
+            return defaults;
+        };
+        match Self::from_json(&saved, &defaults) {
+            Ok(settings) => settings,
+            Err(err) => {
+                log::error!("Could not restore saved settings: {}", err);
+                defaults
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|json| persistence::save_string(SETTINGS_KEY, &json));
+        if let Err(err) = result {
+            log::error!("Could not save settings: {}", err);
+        }
+    }
+
+    /// Reads saved settings, taking settings missing from them, such as settings added since
+    /// they were saved, from `defaults`. Settings saved by a newer version are rejected.
+    pub fn from_json(json: &str, defaults: &ReaderSettings) -> Result<Self, String> {
+        let saved: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let Value::Object(saved) = saved else {
+            return Err("settings are not a JSON object".to_string());
+        };
+        let version = saved.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if version > SETTINGS_VERSION as u64 {
+            return Err(format!(
+                "settings were saved in a newer format (version {})",
+                version
+            ));
+        }
+        let mut settings = serde_json::to_value(defaults).map_err(|e| e.to_string())?;
+        if let Value::Object(settings) = &mut settings {
+            for (key, value) in saved {
+                if key != "version" {
+                    settings.insert(key, value);
+                }
+            }
+        }
+        let mut settings: Self = serde_json::from_value(settings).map_err(|e| e.to_string())?;
+        settings.version = SETTINGS_VERSION;
+        Ok(settings)
+    }
+}
+
+
+/// A paragraph of a document, identified by title and author so that it can be found again
+/// after documents are added to or removed from the library
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReadPosition {
+    pub title: String,
+    pub author: String,
+    /// Paragraph number, 0 for the title
+    pub analysis: usize,
+}
+
+impl ReadPosition {
+    pub fn new(doc: &EmocatOutputDoc, analysis: usize) -> Self {
+        Self {
+            title: doc.title.clone(),
+            author: doc.author.clone(),
+            analysis,
+        }
+    }
+
+    /// Index of the document in `docs`, and the paragraph clamped to the document's
+    /// paragraphs, if the document is still there
+    pub fn locate(&self, docs: &[EmocatOutputDoc]) -> Option<(usize, usize)> {
+        let doc_index = docs
+            .iter()
+            .position(|doc| doc.title == self.title && doc.author == self.author)?;
+        Some((doc_index, self.analysis.min(docs[doc_index].analyses.len())))
+    }
+}
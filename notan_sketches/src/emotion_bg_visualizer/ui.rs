@@ -1,8 +1,12 @@
+use super::options::{OptionKind, VisualizerOption};
 use super::visualizers::color_transition::ColorTransitionVisualizer;
 use super::visualizers::tile::TilesVisualizer;
 use super::visualizers::tiled_shaders::TiledShadersVisualizer;
-use crate::emotion::TopEmotionsModel;
+use super::visualizers::EmoVisualizer;
+use crate::colors::contrast::ContrastMetric;
+use crate::emotion::{EmotionModelError, TopEmotionsModel};
 use notan::egui::{self, RichText, TextStyle, Ui};
+use notan::prelude::Color;
 
 
 fn top_emotions_egui_metrics_ui(
@@ -24,13 +28,33 @@ fn top_emotions_egui_metrics_ui(
     ui.label(header);
     if model.top_emotions.len() > 0 && model.top_emotions[0].score > 0.0 {
         for top_emo in model.top_emotions.iter() {
-            ui.small(format!("{}: {}", top_emo.marker, top_emo.score));
+            ui.small(format!("{}: {}", top_emo.emotion, top_emo.score));
         }
     } else {
         ui.small("None");
     }
 }
 
+fn model_egui_metrics_ui(
+    model: &Option<TopEmotionsModel>,
+    model_error: &Option<EmotionModelError>,
+    ui: &mut Ui,
+    title_style: &dyn Fn() -> TextStyle,
+) {
+    if let Some(model) = model {
+        top_emotions_egui_metrics_ui(model, ui, title_style);
+    } else if let Some(err) = model_error {
+        ui.small(format!("This paragraph could not be analyzed: {}", err));
+    } else {
+        ui.small("The emotion analysis metrics will appear here when you start reading.");
+    }
+}
+
+fn text_contrast_egui_metrics_ui(contrast: f32, metric: ContrastMetric, ui: &mut Ui) {
+    ui.label("");
+    ui.small(format!("Text contrast: {}", metric.format(contrast)));
+}
+
 pub trait DisplayMetrics {
     fn egui_metrics(&self, ui: &mut Ui, title_style: &dyn Fn() -> TextStyle);
 }
@@ -38,33 +62,24 @@ pub trait DisplayMetrics {
 
 impl DisplayMetrics for ColorTransitionVisualizer {
     fn egui_metrics(&self, ui: &mut Ui, title_style: &dyn Fn() -> TextStyle) {
-        if let Some(model) = &self.model {
-            top_emotions_egui_metrics_ui(model, ui, title_style);
-        } else {
-            ui.small("The emotion analysis metrics will appear here when you start reading.");
-        }
+        model_egui_metrics_ui(&self.model, &self.model_error, ui, title_style);
+        text_contrast_egui_metrics_ui(self.get_text_contrast(), self.contrast_metric, ui);
     }
 }
 
 
 impl DisplayMetrics for TilesVisualizer {
     fn egui_metrics(&self, ui: &mut Ui, title_style: &dyn Fn() -> TextStyle) {
-        if let Some(model) = &self.model {
-            top_emotions_egui_metrics_ui(model, ui, title_style);
-        } else {
-            ui.small("The emotion analysis metrics will appear here when you start reading.");
-        }
+        model_egui_metrics_ui(&self.model, &self.model_error, ui, title_style);
+        text_contrast_egui_metrics_ui(self.get_text_contrast(), self.contrast_metric, ui);
     }
 }
 
 
 impl DisplayMetrics for TiledShadersVisualizer {
     fn egui_metrics(&self, ui: &mut Ui, title_style: &dyn Fn() -> TextStyle) {
-        if let Some(model) = &self.model {
-            top_emotions_egui_metrics_ui(model, ui, title_style);
-        } else {
-            ui.small("The emotion analysis metrics will appear here when you start reading.");
-        }
+        model_egui_metrics_ui(&self.model, &self.model_error, ui, title_style);
+        text_contrast_egui_metrics_ui(self.get_text_contrast(), self.contrast_metric, ui);
     }
 }
 
@@ -73,69 +88,56 @@ pub trait SettingsUi {
     fn egui_settings(&mut self, ui: &mut Ui);
 }
 
-impl SettingsUi for ColorTransitionVisualizer {
-    fn egui_settings(&mut self, ui: &mut Ui) {
-        ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
-            let viz_options = &mut Self::get_options();
-
-            ui.label("Color Method");
-            egui::ComboBox::new("color-method", "")
-                .selected_text(&self.color_method)
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                    for option in viz_options.get_mut("Color Method").unwrap().iter() {
-                        ui.selectable_value(&mut self.color_method, option.to_string(), option);
-                    }
-                });
-        });
-    }
-}
-
 
-impl SettingsUi for TilesVisualizer {
-    fn egui_settings(&mut self, ui: &mut Ui) {
-        ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
-            let viz_options = &mut Self::get_options();
-
-            ui.label("Text Shadow");
-            egui::ComboBox::new("shadow-style", "")
-                .selected_text(&self.text_shadow_style)
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                    for option in viz_options.get_mut("Shadow Style").unwrap().iter() {
-                        ui.selectable_value(
-                            &mut self.text_shadow_style,
-                            option.to_string(),
-                            option,
-                        );
-                    }
-                });
-        });
-    }
+/// Shows a visualizer option with the egui widget for its kind
+fn option_egui_setting(ui: &mut Ui, option: VisualizerOption) {
+    ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
+        ui.label(option.label);
+        match option.kind {
+            OptionKind::Choice(field) => {
+                let labels = field.labels();
+                let mut selected = field.selected();
+                egui::ComboBox::new(option.name, "")
+                    .selected_text(labels[selected])
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        for (index, label) in labels.iter().enumerate() {
+                            ui.selectable_value(&mut selected, index, *label);
+                        }
+                    });
+                if selected != field.selected() {
+                    field.select(selected);
+                }
+            }
+            OptionKind::Range {
+                value,
+                range,
+                suffix,
+            } => {
+                ui.add(
+                    egui::Slider::new(value, range)
+                        .suffix(suffix)
+                        .max_decimals(1),
+                );
+            }
+            OptionKind::Toggle(value) => {
+                ui.checkbox(value, "");
+            }
+            OptionKind::Color(color) => {
+                let mut rgba = [color.r, color.g, color.b, color.a];
+                if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                    *color = Color::new(rgba[0], rgba[1], rgba[2], rgba[3]);
+                }
+            }
+        }
+    });
 }
 
-
-impl SettingsUi for TiledShadersVisualizer {
+/// Every visualizer's settings are its `options()`
+impl<T: EmoVisualizer> SettingsUi for T {
     fn egui_settings(&mut self, ui: &mut Ui) {
-        ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
-            // ui.label("There are no options for the Tiles visualizer.");
-            // ui.label(&self.options["Color Method"]);
-
-            let viz_options = &mut Self::get_options();
-
-            ui.label("Text Shadow");
-            egui::ComboBox::new("shadow-style", "")
-                .selected_text(&self.text_shadow_style)
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                    for option in viz_options.get_mut("Shadow Style").unwrap().iter() {
-                        ui.selectable_value(
-                            &mut self.text_shadow_style,
-                            option.to_string(),
-                            option,
-                        );
-                    }
-                });
-        });
+        for option in self.options() {
+            option_egui_setting(ui, option);
+        }
     }
 }
@@ -1,25 +1,225 @@
+pub mod backdrop;
 pub mod color_transition;
 pub mod tile;
 pub mod tiled_shaders;
 
-use crate::emotion::EmocatTextAnalysis;
+use super::harness::VisualizerSnapshot;
+use super::options::{self, OptionChoice, OptionValues, VisualizerOption};
+use crate::colors;
+use crate::colors::contrast::{best_text_color_on_all, ContrastMetric, TextColorChoice};
+use crate::emotion::{
+    get_mapped_emocolor, get_neutral_emocolor, AnalyzerSource, BlendStrategy, ColorMapping,
+    EmoColor, EmocatTextAnalysis, Sentiment, TopEmotionsModel,
+};
+use crate::mathutils::Easing;
+use crate::textutils::{FitBounds, TextFitter};
 use crate::utils::scale_font;
+use backdrop::{draw_paragraph_text, FrostedGlass, ParagraphBounds, ParagraphText, TextBackdrop};
+use color_transition::{
+    ColorMethod, ColorTimeline, ColorTransition, InterpolationSpace, Keyframe, Repeat,
+};
 use notan::draw::*;
-use notan::math::Vec2;
+use notan::math::{vec2, Vec2};
 use notan::prelude::*;
+use palette::{Darken, FromColor, Lighten, Srgb};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 
 const TITLE_COLOR: Color = Color::BLACK;
 const META_COLOR: Color = Color::GRAY;
-const FONT_RESIZE_STEP: f32 = 2.0;
+const TITLE_FONT_SIZE: f32 = 60.0;
+const TITLE_MIN_FONT_SIZE: f32 = 30.0;
+const PARAGRAPH_FONT_SIZE: f32 = 32.0;
+const PARAGRAPH_MIN_FONT_SIZE: f32 = 16.0;
+/// Background shown when a paragraph's analysis cannot be modeled
+pub const MODEL_ERROR_COLOR: Color = Color::GRAY;
+/// Text colors the visualizers choose from, unless another `TextPalette` is selected
+pub const DEFAULT_TEXT_PALETTE: [Color; 2] = [Color::BLACK, Color::WHITE];
+const SOFT_TEXT_PALETTE: [Color; 2] = [
+    Color::new(0.2, 0.2, 0.2, 1.0),
+    Color::new(0.96, 0.96, 0.94, 1.0),
+];
+const SEPIA_TEXT_PALETTE: [Color; 2] = [
+    Color::new(0.24, 0.16, 0.1, 1.0),
+    Color::new(0.96, 0.92, 0.84, 1.0),
+];
+const VIVID_TEXT_PALETTE: [Color; 4] =
+    [Color::BLACK, Color::WHITE, colors::BANANA, colors::PEACOCK];
+/// Slightly increases the sentiment score for use as a value to brighten/darken HSV
+const VALUE_MODIFIER: f32 = 3.0;
+const MINIMAL_ENHANCEMENT: f32 = 0.05;
+// const MINIMAL_ENHANCEMENT: f32 = 0.1;
+/// How many of a paragraph's strongest emotions the tile visualizers pulse through
+const TILE_EMOTIONS: usize = 3;
 
 
-#[derive(PartialEq)]
+/// Emphasis on one line of a paragraph, such as the line being read along with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineHighlight {
+    /// Index of the line in the paragraph's text
+    pub line: usize,
+    pub color: Color,
+}
+
+
+/// How `draw_paragraph()` laid out a paragraph
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParagraphLayout {
+    /// The page drawn
+    pub page: usize,
+    pub pages: usize,
+    /// Indices of the lines of the paragraph on the page
+    pub lines: Range<usize>,
+}
+
+
+/// Colors the text color is chosen from, picking the one with the best contrast on the
+/// background
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextPalette {
+    #[default]
+    BlackWhite,
+    /// Charcoal and off-white, for less glare
+    Soft,
+    /// Dark brown and cream
+    Sepia,
+    /// Black and white, or yellow and teal where they stand out more
+    Vivid,
+}
+
+impl TextPalette {
+    pub const ALL: [TextPalette; 4] = [
+        TextPalette::BlackWhite,
+        TextPalette::Soft,
+        TextPalette::Sepia,
+        TextPalette::Vivid,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextPalette::BlackWhite => "Black & White",
+            TextPalette::Soft => "Soft",
+            TextPalette::Sepia => "Sepia",
+            TextPalette::Vivid => "Vivid",
+        }
+    }
+
+    pub fn colors(&self) -> &'static [Color] {
+        match self {
+            TextPalette::BlackWhite => &DEFAULT_TEXT_PALETTE,
+            TextPalette::Soft => &SOFT_TEXT_PALETTE,
+            TextPalette::Sepia => &SEPIA_TEXT_PALETTE,
+            TextPalette::Vivid => &VIVID_TEXT_PALETTE,
+        }
+    }
+}
+
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum VisualizerSelection {
     ColorTransition,
     Tiles,
     TiledShaders,
 }
 
+
+/// The options all visualizers have
+pub fn common_options<'a>(
+    text_backdrop: &'a mut TextBackdrop,
+    transition: &'a mut ColorTransition,
+    contrast_metric: &'a mut ContrastMetric,
+    text_palette: &'a mut TextPalette,
+    analyzer_source: &'a mut AnalyzerSource,
+) -> Vec<VisualizerOption<'a>> {
+    vec![
+        VisualizerOption::choice("text_backdrop", "Text Backdrop", text_backdrop),
+        VisualizerOption::choice("transition_easing", "Transition", &mut transition.easing),
+        VisualizerOption::range(
+            "transition_duration",
+            "Transition Duration",
+            &mut transition.duration,
+            0.5..=20.0,
+            " s",
+        ),
+        VisualizerOption::choice("transition_space", "Color Space", &mut transition.space),
+        VisualizerOption::choice("contrast_metric", "Text Contrast", contrast_metric),
+        VisualizerOption::choice("text_palette", "Text Colors", text_palette),
+        VisualizerOption::choice("analyzer_source", "Analyzer", analyzer_source),
+    ]
+}
+
+
+impl OptionChoice for TextBackdrop {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        TextBackdrop::label(self)
+    }
+}
+
+impl OptionChoice for Easing {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        Easing::label(self)
+    }
+}
+
+impl OptionChoice for InterpolationSpace {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        InterpolationSpace::label(self)
+    }
+}
+
+impl OptionChoice for ContrastMetric {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        ContrastMetric::label(self)
+    }
+}
+
+impl OptionChoice for TextPalette {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        TextPalette::label(self)
+    }
+}
+
+impl OptionChoice for AnalyzerSource {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        AnalyzerSource::label(self)
+    }
+}
+
+impl OptionChoice for ColorMethod {
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn label(&self) -> &'static str {
+        ColorMethod::label(self)
+    }
+}
+
+
 pub trait EmoVisualizer {
     fn get_enum(&self) -> VisualizerSelection;
 
@@ -40,10 +240,67 @@ pub trait EmoVisualizer {
     /// the analysis.
     fn update_model(&mut self, analysis: &EmocatTextAnalysis);
 
-    fn update_visualization(&mut self);
+    /// Advances transitions by `delta` seconds, the time since the previous frame
+    fn update_visualization(&mut self, delta: f32);
+
+    /// Lays out anything that depends on the size of the drawing area, such as tiles.
+    /// Called every frame from `draw()`, or directly when running headless.
+    fn update_layout(&mut self, _work_size: Vec2) {}
+
+    /// Replaces the random number generator with one seeded with `seed`, so that the output
+    /// can be reproduced. Visualizers without randomness can ignore this.
+    fn reseed(&mut self, _seed: u64) {}
+
+    /// Records the colors and layout that would currently be drawn
+    fn snapshot(&self) -> VisualizerSnapshot;
+
+    /// Takes effect from the next call to `update_model()`
+    fn set_color_mapping(&mut self, color_mapping: ColorMapping);
+
+    /// The options the user can configure, which `SettingsUi::egui_settings()` shows and
+    /// which are saved between sessions
+    fn options(&mut self) -> Vec<VisualizerOption<'_>>;
+
+    fn save_options(&mut self) -> OptionValues {
+        options::save_options(&self.options())
+    }
+
+    /// Takes effect from the next call to `update_model()`
+    fn restore_options(&mut self, values: &OptionValues) {
+        options::restore_options(&mut self.options(), values);
+    }
+
+    /// Whether the background has finished transitioning to the colors of the current
+    /// analysis, see `ColorTransition::is_complete()`
+    fn is_transition_complete(&self) -> bool;
+
+    /// The analyzer the models are based on, which the document's emotion arc should use too
+    fn get_analyzer_source(&self) -> AnalyzerSource;
+
+    /// How a paragraph's emotions are blended into the one color shown for it, such as in the
+    /// fingerprint strip
+    fn get_blend_strategy(&self) -> BlendStrategy {
+        BlendStrategy::Weighted
+    }
 
     fn get_text_color(&self) -> Color;
 
+    /// Contrast of the text color on the colors behind the paragraph, measured with the
+    /// visualizer's `ContrastMetric`
+    fn get_text_contrast(&self) -> f32;
+
+    /// How the paragraph is set apart from the visualization, see `draw_paragraph()`
+    fn get_text_backdrop(&self) -> TextBackdrop;
+
+    /// The blurred visualization for `TextBackdrop::Frosted`, captured at the end of `draw()`
+    fn get_frosted_glass(&self) -> Option<&FrostedGlass> {
+        None
+    }
+
+    /// Fits the title and paragraphs to the drawing area, see `draw_title()` and
+    /// `draw_paragraph()`
+    fn get_text_fitter(&mut self) -> &mut TextFitter;
+
     fn draw(&mut self, app: &mut App, graphics: &mut Graphics, draw: &mut Draw);
 
 
@@ -56,12 +313,26 @@ pub trait EmoVisualizer {
         work_size: Vec2,
     ) {
         let mut textbox_width = work_size.x * 0.75;
+        let title_size = self
+            .get_text_fitter()
+            .fit(
+                draw,
+                title_font,
+                title,
+                FitBounds {
+                    max_width: textbox_width,
+                    max_height: work_size.y * 0.4,
+                    min_size: scale_font(TITLE_MIN_FONT_SIZE, work_size),
+                    max_size: scale_font(TITLE_FONT_SIZE, work_size),
+                },
+            )
+            .size;
 
         draw.text(&title_font, &title)
             .alpha_mode(BlendMode::OVER) // Fixes some artifacting -- gonna be default in future Notan
             .color(TITLE_COLOR)
             // NOTE: These draw.text fonts size differently than font sizes in egui
-            .size(scale_font(60.0, work_size))
+            .size(title_size)
             .max_width(textbox_width)
             .position(work_size.x * 0.5 - textbox_width * 0.5, work_size.y * 0.4)
             .h_align_left()
@@ -83,21 +354,66 @@ pub trait EmoVisualizer {
             .v_align_middle();
     }
 
-    fn draw_paragraph(&mut self, draw: &mut Draw, font: &Font, text: &str, work_size: Vec2) {
+    /// Draws a page of the paragraph, or its last page if it has fewer pages. Paragraphs are
+    /// split into pages when they don't fit even at the minimum font size.
+    fn draw_paragraph(
+        &mut self,
+        draw: &mut Draw,
+        font: &Font,
+        text: &str,
+        page: usize,
+        highlight: Option<LineHighlight>,
+        work_size: Vec2,
+    ) -> ParagraphLayout {
         let max_width = work_size.x * 0.75;
         let max_height = work_size.y * 0.75;
 
-        let font_size =
-            get_optimal_text_size(draw, font, text, work_size, 32.0, max_width, max_height);
-        draw.text(&font, &text)
-            .alpha_mode(BlendMode::OVER)
-            .color(self.get_text_color())
-            // NOTE: These draw.text fonts size differently than font sizes in egui
-            .size(scale_font(font_size, work_size))
-            .max_width(max_width)
-            .position(work_size.x * 0.5 - max_width * 0.5, work_size.y * 0.5)
-            .v_align_middle()
-            .h_align_left();
+        let fit = self
+            .get_text_fitter()
+            .fit(
+                draw,
+                font,
+                text,
+                FitBounds {
+                    max_width,
+                    max_height,
+                    min_size: scale_font(PARAGRAPH_MIN_FONT_SIZE, work_size),
+                    max_size: scale_font(PARAGRAPH_FONT_SIZE, work_size),
+                },
+            )
+            .clone();
+        let page = page.min(fit.pages.len().saturating_sub(1));
+        let (page_text, lines) = match fit.pages.get(page) {
+            Some(text_page) => (text_page.text.as_str(), text_page.lines()),
+            None => (text, 0..text.split('\n').count()),
+        };
+        let paragraph = ParagraphText {
+            font,
+            text: page_text,
+            position: vec2(work_size.x * 0.5 - max_width * 0.5, work_size.y * 0.5),
+            size: fit.size,
+            max_width,
+            color: self.get_text_color(),
+            highlight: highlight
+                .filter(|highlight| lines.contains(&highlight.line))
+                .map(|highlight| LineHighlight {
+                    line: highlight.line - lines.start,
+                    ..highlight
+                }),
+        };
+        let bounds = ParagraphBounds::measure(self.get_text_fitter(), draw, &paragraph);
+        draw_paragraph_text(
+            draw,
+            &paragraph,
+            &bounds,
+            self.get_text_backdrop(),
+            self.get_frosted_glass(),
+        );
+        ParagraphLayout {
+            page,
+            pages: fit.pages.len().max(1),
+            lines,
+        }
     }
 
     fn draw_read_help(
@@ -136,54 +452,145 @@ pub trait EmoVisualizer {
 }
 
 
-/// Draw text and check bounds recursively to find nearest fit for oversized text
-pub fn get_optimal_text_size(
-    draw: &mut Draw,
-    font: &Font,
-    text: &str,
-    work_size: Vec2,
-    font_size: f32,
-    max_width: f32,
-    max_height: f32,
-) -> f32 {
-    draw.text(&font, &text)
-        .alpha_mode(BlendMode::OVER)
-        .color(Color::TRANSPARENT)
-        .size(scale_font(font_size, work_size))
-        .max_width(max_width)
-        .position(work_size.x * 0.5 - max_width * 0.5, work_size.y * 0.5)
-        .v_align_middle()
-        .h_align_left();
-
-    let para_bounds = draw.last_text_bounds();
-    // log::debug!("{} > {}?", para_bounds.height, max_height);
-    if para_bounds.height > max_height {
-        let font_size = font_size - FONT_RESIZE_STEP;
-        // log::debug!("Font resized to {}", font_size);
-        return get_optimal_text_size(
-            draw, font, text, work_size, font_size, max_width, max_height,
-        );
-    }
-    font_size
+/// Return black or white, whichever has the higher WCAG contrast on the background color
+pub fn get_optimal_text_color(bgcolor: &Color) -> Color {
+    choose_text_color(&[*bgcolor], &DEFAULT_TEXT_PALETTE, ContrastMetric::Wcag).color
 }
 
-/// Return black or white depending on provided background color
-///
-/// Based on this algorithm:
-/// https://stackoverflow.com/a/1855903/4655636
-///
-/// NOTE: There is a follow-up that seems to propose a more "correct" calculation.
-/// Investigate if necessary:
-/// https://stackoverflow.com/a/69869976/4655636
+
+/// The color from `palette` with the best contrast on all of the `backgrounds`, along with
+/// that contrast. Chooses from `DEFAULT_TEXT_PALETTE` when the palette is empty.
+pub fn choose_text_color(
+    backgrounds: &[Color],
+    palette: &[Color],
+    metric: ContrastMetric,
+) -> TextColorChoice {
+    best_text_color_on_all(backgrounds, palette, metric)
+        .or_else(|| best_text_color_on_all(backgrounds, &DEFAULT_TEXT_PALETTE, metric))
+        .unwrap_or(TextColorChoice {
+            color: Color::BLACK,
+            contrast: 0.0,
+        })
+}
+
+
+/// Top left corner and size of the box that `draw_paragraph()` fits paragraphs in
+pub fn get_paragraph_area(work_size: Vec2) -> (Vec2, Vec2) {
+    let size = vec2(work_size.x * 0.75, work_size.y * 0.75);
+    (work_size * 0.5 - size * 0.5, size)
+}
+
+
+/// Whether the rectangle overlaps the box returned by `get_paragraph_area()`
+pub fn overlaps_paragraph_area(pos: Vec2, size: Vec2, work_size: Vec2) -> bool {
+    let (area_pos, area_size) = get_paragraph_area(work_size);
+    pos.x < area_pos.x + area_size.x
+        && pos.x + size.x > area_pos.x
+        && pos.y < area_pos.y + area_size.y
+        && pos.y + size.y > area_pos.y
+}
+
+
+/// Represents different Tile "baselines/archetypes", shared by the tile visualizers
 ///
-pub fn get_optimal_text_color(bgcolor: &Color) -> Color {
-    let luminance: f32;
-    luminance =
-        (0.299 * (bgcolor.r * 255.0) + 0.587 * (bgcolor.g * 255.0) + 0.114 * (bgcolor.b * 255.0))
-            / 255.0;
-    // log::debug!("Luminance {}", luminance);
-    if luminance < 0.5 {
-        return Color::WHITE;
-    }
-    Color::BLACK
+/// For now just contains an EmoColor, but anticipating expansion
+struct Tile {
+    emocolor: EmoColor,
+}
+
+impl Tile {
+    /// One tile for each of the paragraph's `TILE_EMOTIONS` strongest emotions that scored
+    /// above zero, or a single neutral tile when none did
+    fn from_model(model: &TopEmotionsModel, color_mapping: &ColorMapping) -> Vec<Self> {
+        let tiles: Vec<Self> = model
+            .scores
+            .iter()
+            .filter(|s| s.score > 0.0)
+            .take(TILE_EMOTIONS)
+            .map(|s| Self {
+                emocolor: get_mapped_emocolor(s.emotion, color_mapping),
+            })
+            .collect();
+        if tiles.is_empty() {
+            return vec![Self {
+                emocolor: get_neutral_emocolor(),
+            }];
+        }
+        tiles
+    }
+}
+
+
+fn get_sentiment_enhanced_color(
+    emocolor: &EmoColor,
+    rng: &mut Random,
+    positive_sentiment: f32,
+    negative_sentiment: f32,
+) -> Color {
+    let mut hsv_color = emocolor.hsv.clone();
+    hsv_color = match emocolor.sentiment {
+        Sentiment::POSITIVE => {
+            if positive_sentiment > 0.0 {
+                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
+            } else {
+                // In some cases, with nrclex, it seems the sentiment score can be zero even if the
+                // emotion is associated to a sentiment. In such cases, use a very minimal range.
+                //
+                // @TODO: Investigate whether there is a bug/error in the emocat nrclex adapter
+                hsv_color.lighten(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
+            }
+        }
+        Sentiment::NEGATIVE => {
+            if negative_sentiment > 0.0 {
+                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
+            } else {
+                // See comment in positive sentiment arm above
+                hsv_color.darken(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
+            }
+        }
+        _ => {
+            if positive_sentiment > negative_sentiment {
+                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
+            } else if negative_sentiment > positive_sentiment {
+                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
+            } else {
+                hsv_color
+            }
+        }
+    };
+    let srgb = Srgb::from_color(hsv_color);
+    Color::from_rgb(srgb.red, srgb.green, srgb.blue)
+}
+
+
+/// A timeline from the tile's current color through the colors of all the tile archetypes,
+/// starting from a random one, so that tiles pulse through the paragraph's top emotions.
+/// Each color is enhanced by the sentiment, and the keyframes follow the background
+/// transition's duration, easing and color space.
+fn tile_timeline(
+    from: Color,
+    tiles: &[Tile],
+    model: &TopEmotionsModel,
+    transition: &ColorTransition,
+    rng: &mut Random,
+) -> ColorTimeline {
+    let first_tile = if tiles.len() > 1 {
+        rng.random_range(0..tiles.len())
+    } else {
+        0
+    };
+    let mut keyframes = vec![Keyframe::new(from, 0.0, transition.easing)];
+    for i in 0..tiles.len() {
+        let tile = &tiles[(first_tile + i) % tiles.len()];
+        let color =
+            get_sentiment_enhanced_color(&tile.emocolor, rng, model.positive, model.negative);
+        keyframes.push(Keyframe::new(
+            color,
+            (i + 1) as f32 * transition.duration,
+            transition.easing,
+        ));
+    }
+    let mut timeline = ColorTimeline::new(keyframes, Repeat::Hold);
+    timeline.space = transition.space;
+    timeline
 }
@@ -0,0 +1,341 @@
+use super::{get_optimal_text_color, LineHighlight};
+use crate::textutils::TextFitter;
+use notan::draw::*;
+use notan::log;
+use notan::math::{vec2, Vec2};
+use notan::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// NOTE: You will need to `touch` this file to re-compile if the shader below is changed
+const BLUR_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/frosted_blur.frag.glsl");
+
+/// Pixels between the taps of the blur, as a fraction of the height of the drawing area
+const BLUR_STEP: f32 = 0.004;
+const SHADOW_ALPHA: f32 = 0.12;
+const OUTLINE_ALPHA: f32 = 0.9;
+const PANEL_ALPHA: f32 = 0.55;
+/// Tint over the blurred background, so that the frosted panel reads as a surface
+const FROST_TINT_ALPHA: f32 = 0.25;
+const HIGHLIGHT_ALPHA: f32 = 0.35;
+/// Opacity of the lines around a highlighted line
+const DIMMED_TEXT_ALPHA: f32 = 0.5;
+
+
+/// How the paragraph is set apart from the visualization behind it, to keep it legible
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextBackdrop {
+    #[default]
+    None,
+    /// Soft shadow below and to the left of the text
+    Shadow,
+    /// Stroke around each letter
+    Outline,
+    /// Translucent rounded panel behind the paragraph
+    Panel,
+    /// Blurred copy of the visualization behind the paragraph, like frosted glass
+    Frosted,
+}
+
+impl TextBackdrop {
+    pub const ALL: [TextBackdrop; 5] = [
+        TextBackdrop::None,
+        TextBackdrop::Shadow,
+        TextBackdrop::Outline,
+        TextBackdrop::Panel,
+        TextBackdrop::Frosted,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextBackdrop::None => "None",
+            TextBackdrop::Shadow => "Drop shadow",
+            TextBackdrop::Outline => "Outline",
+            TextBackdrop::Panel => "Panel",
+            TextBackdrop::Frosted => "Frosted glass",
+        }
+    }
+}
+
+
+/// A paragraph laid out by `EmoVisualizer::draw_paragraph()`
+pub struct ParagraphText<'a> {
+    pub font: &'a Font,
+    pub text: &'a str,
+    /// Left edge and vertical middle of the text
+    pub position: Vec2,
+    /// Font size, already scaled to the work size
+    pub size: f32,
+    pub max_width: f32,
+    pub color: Color,
+    /// Line of `text` to emphasize, with the others dimmed
+    pub highlight: Option<LineHighlight>,
+}
+
+impl ParagraphText<'_> {
+    fn draw_at(&self, draw: &mut Draw, offset: Vec2, color: Color, alpha: f32) {
+        draw.text(self.font, self.text)
+            .alpha_mode(BlendMode::OVER)
+            .alpha(alpha)
+            .color(color)
+            // NOTE: These draw.text fonts size differently than font sizes in egui
+            .size(self.size)
+            .max_width(self.max_width)
+            .position(self.position.x + offset.x, self.position.y + offset.y)
+            .v_align_middle()
+            .h_align_left();
+    }
+}
+
+
+/// Where a `ParagraphText` is drawn, measured ahead of drawing it
+pub struct ParagraphBounds {
+    /// Top left corner of the text
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Top and size of the highlighted line, if there is a highlight on a line with text
+    pub highlight: Option<(f32, Vec2)>,
+}
+
+impl ParagraphBounds {
+    /// Measures the paragraph with the fitter, which only draws text to measure it the first
+    /// time a text is measured at a size
+    pub fn measure(fitter: &mut TextFitter, draw: &mut Draw, paragraph: &ParagraphText) -> Self {
+        let mut measure = |text: &str| {
+            fitter.measure(
+                draw,
+                paragraph.font,
+                text,
+                paragraph.size,
+                paragraph.max_width,
+            )
+        };
+        let size = measure(paragraph.text);
+        // The text is drawn centered vertically on the paragraph's position
+        let top = paragraph.position.y - size.y * 0.5;
+        let highlight = paragraph.highlight.and_then(|highlight| {
+            let line = paragraph.text.split('\n').nth(highlight.line)?;
+            if line.trim().is_empty() {
+                return None;
+            }
+            // The line's top is found from the height of the text up to and including the
+            // line, since long lines wrap
+            let through_line = paragraph
+                .text
+                .split('\n')
+                .take(highlight.line + 1)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let through_size = measure(&through_line);
+            let line_size = measure(line);
+            Some((top + through_size.y - line_size.y, line_size))
+        });
+        Self {
+            position: vec2(paragraph.position.x, top),
+            size,
+            highlight,
+        }
+    }
+}
+
+
+/// Draws the paragraph over the given backdrop. The backdrop is drawn in black or white,
+/// whichever contrasts more with the text color.
+///
+/// `frosted` is the blurred visualization for `TextBackdrop::Frosted`. A plain panel is drawn
+/// instead until one has been captured.
+pub fn draw_paragraph_text(
+    draw: &mut Draw,
+    paragraph: &ParagraphText,
+    bounds: &ParagraphBounds,
+    backdrop: TextBackdrop,
+    frosted: Option<&FrostedGlass>,
+) {
+    let backdrop_color = get_optimal_text_color(&paragraph.color);
+    match backdrop {
+        TextBackdrop::None => {}
+        TextBackdrop::Shadow => {
+            // Copies of the text scattered around the shadow's offset approximate a blur
+            let offset = vec2(-1.0, 1.0) * paragraph.size * 0.06;
+            let spread = paragraph.size * 0.04;
+            paragraph.draw_at(draw, offset, backdrop_color, SHADOW_ALPHA * 2.0);
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                let jitter = vec2(angle.cos(), angle.sin()) * spread;
+                paragraph.draw_at(draw, offset + jitter, backdrop_color, SHADOW_ALPHA);
+            }
+        }
+        TextBackdrop::Outline => {
+            let width = (paragraph.size * 0.04).max(1.0);
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                let offset = vec2(angle.cos(), angle.sin()) * width;
+                paragraph.draw_at(draw, offset, backdrop_color, OUTLINE_ALPHA);
+            }
+        }
+        TextBackdrop::Panel | TextBackdrop::Frosted => {
+            let (pos, size) = panel_bounds(paragraph, bounds);
+            match (backdrop, frosted) {
+                (TextBackdrop::Frosted, Some(glass)) => {
+                    draw.image(glass.texture())
+                        .crop((pos.x, pos.y), (size.x, size.y))
+                        .position(pos.x, pos.y)
+                        .size(size.x, size.y);
+                    draw.rect((pos.x, pos.y), (size.x, size.y))
+                        .color(backdrop_color)
+                        .alpha(FROST_TINT_ALPHA);
+                }
+                _ => {
+                    draw.rect((pos.x, pos.y), (size.x, size.y))
+                        .corner_radius(paragraph.size * 0.5)
+                        .color(backdrop_color)
+                        .alpha(PANEL_ALPHA);
+                }
+            }
+        }
+    }
+    match (paragraph.highlight, bounds.highlight) {
+        (Some(highlight), Some((line_top, line_size))) => {
+            draw_highlighted_text(draw, paragraph, highlight, line_top, line_size)
+        }
+        // A highlight on an empty line leaves nothing to emphasize
+        (Some(_), None) => paragraph.draw_at(draw, Vec2::ZERO, paragraph.color, DIMMED_TEXT_ALPHA),
+        _ => paragraph.draw_at(draw, Vec2::ZERO, paragraph.color, 1.0),
+    }
+}
+
+
+/// Draws the paragraph dimmed, except for the highlighted line, which is drawn over a rounded
+/// bar in the highlight's color
+fn draw_highlighted_text(
+    draw: &mut Draw,
+    paragraph: &ParagraphText,
+    highlight: LineHighlight,
+    line_top: f32,
+    line_size: Vec2,
+) {
+    let line = paragraph.text.split('\n').nth(highlight.line).unwrap_or("");
+    let padding = paragraph.size * 0.2;
+    draw.rect(
+        (paragraph.position.x - padding, line_top - padding),
+        (line_size.x + padding * 2.0, line_size.y + padding * 2.0),
+    )
+    .corner_radius(padding * 2.0)
+    .color(highlight.color)
+    .alpha(HIGHLIGHT_ALPHA);
+    paragraph.draw_at(draw, Vec2::ZERO, paragraph.color, DIMMED_TEXT_ALPHA);
+    draw.text(paragraph.font, line)
+        .alpha_mode(BlendMode::OVER)
+        .color(paragraph.color)
+        .size(paragraph.size)
+        .max_width(paragraph.max_width)
+        .position(paragraph.position.x, line_top)
+        .v_align_top()
+        .h_align_left();
+}
+
+
+/// Top left corner and size of a panel around the paragraph, with some padding
+fn panel_bounds(paragraph: &ParagraphText, bounds: &ParagraphBounds) -> (Vec2, Vec2) {
+    let padding = paragraph.size * 0.8;
+    (bounds.position - padding, bounds.size + padding * 2.0)
+}
+
+
+/// A blurred copy of everything a visualizer has drawn, for `TextBackdrop::Frosted`. The
+/// blur is separable, running horizontally into one render texture and then vertically back
+/// into the other.
+pub struct FrostedGlass {
+    /// The captured visualization, blurred in place by `capture()`
+    scene: RenderTexture,
+    half_blurred: RenderTexture,
+    pipeline: Pipeline,
+    horizontal_ubo: Buffer,
+    vertical_ubo: Buffer,
+}
+
+impl FrostedGlass {
+    pub fn new(gfx: &mut Graphics, work_size: Vec2) -> Result<Self, String> {
+        let step = work_size.y * BLUR_STEP;
+        let create_rt = |gfx: &mut Graphics| {
+            gfx.create_render_texture(work_size.x as _, work_size.y as _)
+                .with_filter(TextureFilter::Linear, TextureFilter::Linear)
+                .build()
+        };
+        Ok(Self {
+            scene: create_rt(gfx)?,
+            half_blurred: create_rt(gfx)?,
+            pipeline: create_image_pipeline(gfx, Some(&BLUR_FRAG))?,
+            horizontal_ubo: gfx
+                .create_uniform_buffer(1, "Blur")
+                .with_data(&[step, 0.0])
+                .build()?,
+            vertical_ubo: gfx
+                .create_uniform_buffer(1, "Blur")
+                .with_data(&[0.0, step])
+                .build()?,
+        })
+    }
+
+    /// Renders what has been drawn to `scene` so far, and blurs it
+    pub fn capture(&mut self, gfx: &mut Graphics, scene: &Draw) {
+        gfx.render_to(&self.scene, scene);
+        self.blur_pass(gfx, true);
+        self.blur_pass(gfx, false);
+    }
+
+    /// The blurred visualization, the same size as the work size it was created for
+    pub fn texture(&self) -> &Texture {
+        &self.scene
+    }
+
+    fn size(&self) -> Vec2 {
+        let (width, height) = self.scene.size();
+        vec2(width, height)
+    }
+
+    fn blur_pass(&self, gfx: &mut Graphics, horizontal: bool) {
+        let (source, target, ubo) = if horizontal {
+            (&self.scene, &self.half_blurred, &self.horizontal_ubo)
+        } else {
+            (&self.half_blurred, &self.scene, &self.vertical_ubo)
+        };
+        let mut draw = target.create_draw();
+        draw.clear(Color::BLACK);
+        draw.image_pipeline()
+            .pipeline(&self.pipeline)
+            .uniform_buffer(ubo);
+        draw.image(source);
+        draw.image_pipeline().remove();
+        gfx.render_to(target, &draw);
+    }
+}
+
+
+/// Captures `scene` into `glass` when `backdrop` is `TextBackdrop::Frosted`, creating it or
+/// recreating it at the new size as needed. The render textures are dropped for other
+/// backdrops.
+pub fn update_frosted_glass(
+    glass: &mut Option<FrostedGlass>,
+    backdrop: TextBackdrop,
+    gfx: &mut Graphics,
+    scene: &Draw,
+) {
+    if backdrop != TextBackdrop::Frosted {
+        *glass = None;
+        return;
+    }
+    let work_size = vec2(scene.width(), scene.height());
+    if !matches!(glass, Some(g) if g.size() == work_size.floor()) {
+        *glass = match FrostedGlass::new(gfx, work_size) {
+            Ok(new_glass) => Some(new_glass),
+            Err(err) => {
+                log::error!("Could not create the frosted glass backdrop: {}", err);
+                None
+            }
+        };
+    }
+    if let Some(glass) = glass.as_mut() {
+        glass.capture(gfx, scene);
+    }
+}
@@ -1,103 +1,472 @@
-use super::get_optimal_text_color;
+use super::super::harness::VisualizerSnapshot;
+use super::super::options::VisualizerOption;
+use super::backdrop::{update_frosted_glass, FrostedGlass, TextBackdrop};
+use super::choose_text_color;
 use super::EmoVisualizer;
-use super::VisualizerSelection;
-use crate::emotion::{EmocatTextAnalysis, TopEmotionsModel};
+use super::{common_options, VisualizerSelection};
+use super::{TextPalette, MODEL_ERROR_COLOR};
+use crate::colors::contrast::ContrastMetric;
+use crate::emotion::{
+    AnalyzerSource, BlendStrategy, ColorMapping, EmocatTextAnalysis, EmotionModelError,
+    TopEmotionsModel,
+};
+use crate::mathutils::Easing;
+use crate::textutils::TextFitter;
 use notan::draw::*;
-// use notan::log;
+use notan::log;
 use notan::prelude::*;
-use palette::{FromColor, LinSrgb, Mix, Srgb};
-use std::collections::HashMap;
-
-const COLOR_COMPARISON_PRECISION: f32 = 3.0;
-const STARTING_MIX_FACTOR: f32 = 0.0;
-/// Because vsync in wasm seems to be non-negotiable (I think), we need a faster mix rate
-/// to match what it looks like in native
-#[cfg(target_arch = "wasm32")]
-// const MIX_RATE: f32 = 0.1;
-const MIX_RATE: f32 = 0.0001;
-#[cfg(not(target_arch = "wasm32"))]
-const MIX_RATE: f32 = 0.00001;
-// const MIX_RATE: f32 = 0.000001;
-
-fn round(val: f32, digits: f32) -> f32 {
-    // log::debug!("{}, {}", val, (val * 100.0).round() / 100.0);
-    // (val * 100.0).round() / 100.0
-
-    let mut multiplier: f32 = 10.0;
-    multiplier = multiplier.powf(digits);
-    // log::debug!("{}, {}", val, (val * multiplier).round() / multiplier);
-    (val * multiplier).round() / multiplier
-}
+use palette::{FromColor, Hsv, LinSrgb, Oklab, Oklch, Srgb};
+use serde::{Deserialize, Serialize};
+
+/// Seconds a color transition takes, unless its `duration` is changed
+pub const DEFAULT_TRANSITION_DURATION: f32 = 6.0;
 
+
+/// Transitions `color` to `target_color` over `duration` seconds of `step()` calls, so that
+/// transitions take the same time whatever the frame rate. Changing the target, either with
+/// `set_target()` or by setting `target_color`, starts a new transition from the current color.
 #[derive(Clone)]
 pub struct ColorTransition {
     pub target_color: Color,
     pub color: Color,
-    pub mix_factor: f32,
+    /// Seconds a transition takes
+    pub duration: f32,
+    pub easing: Easing,
+    pub space: InterpolationSpace,
     pub transitioning: bool,
+    from_color: Color,
+    /// Target of the current transition, to detect changes of `target_color`
+    to_color: Color,
+    elapsed: f32,
 }
 
 impl Default for ColorTransition {
     fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+impl ColorTransition {
+    /// A transition resting on `color`
+    pub fn new(color: Color) -> Self {
         Self {
-            color: Color::WHITE,
-            target_color: Color::WHITE,
-            mix_factor: STARTING_MIX_FACTOR,
+            target_color: color,
+            color,
+            duration: DEFAULT_TRANSITION_DURATION,
+            easing: Easing::default(),
+            space: InterpolationSpace::default(),
             transitioning: false,
+            from_color: color,
+            to_color: color,
+            elapsed: 0.0,
         }
     }
-}
 
-impl ColorTransition {
+    pub fn set_target(&mut self, color: Color) {
+        self.target_color = color;
+        self.start_if_retargeted();
+    }
+
+    /// Jumps to the target color, ending the transition
     pub fn immediate(&mut self) {
-        self.color = self.target_color.clone();
+        self.color = self.target_color;
+        self.from_color = self.target_color;
+        self.to_color = self.target_color;
+        self.elapsed = self.duration;
         self.transitioning = false;
     }
 
-    pub fn step(&mut self) {
-        // The mix function used to blend colors below doesn't always end up with the
-        // exact floating point numbers of the end color, so comparing with rounded
-        // color values instead of comparing the colors directly.
-        let precision = COLOR_COMPARISON_PRECISION;
-        // log::debug!(
-        //     "{}::{}, {}::{}, {}::{}",
-        //     round(self.color.r, precision),
-        //     round(self.target_color.r, precision),
-        //     round(self.color.g, precision),
-        //     round(self.target_color.g, precision),
-        //     round(self.color.b, precision),
-        //     round(self.target_color.b, precision),
-        // );
-        if round(self.color.r, precision) != round(self.target_color.r, precision)
-            || round(self.color.g, precision) != round(self.target_color.g, precision)
-            || round(self.color.b, precision) != round(self.target_color.b, precision)
-        {
+    /// Linear progress of the current transition, from 0.0 to 1.0. 1.0 when not transitioning.
+    pub fn progress(&self) -> f32 {
+        if !self.transitioning || self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// True once the color has reached the target. A target that was changed but not picked up
+    /// by `step()` yet counts as a transition that hasn't completed, so this can be relied on
+    /// right after setting `target_color`.
+    pub fn is_complete(&self) -> bool {
+        !self.transitioning && self.target_color == self.to_color
+    }
+
+    /// Advances the transition by `delta` seconds. Returns `true` on the step that completes
+    /// the transition, and `false` otherwise.
+    pub fn step(&mut self, delta: f32) -> bool {
+        self.start_if_retargeted();
+        if !self.transitioning {
+            return false;
+        }
+        self.elapsed += delta.max(0.0);
+        if self.elapsed >= self.duration {
+            self.immediate();
+            return true;
+        }
+        self.color = self.space.mix(
+            self.from_color,
+            self.to_color,
+            self.easing.apply(self.progress()),
+        );
+        false
+    }
+
+    fn start_if_retargeted(&mut self) {
+        if self.target_color != self.to_color {
+            self.from_color = self.color;
+            self.to_color = self.target_color;
+            self.elapsed = 0.0;
             self.transitioning = true;
-            // log::debug!("Mix factor: {}", state.bg_color_mix_factor);
-            let bg_color = Srgb::new(self.color.r, self.color.g, self.color.b);
-            let target_color = Srgb::new(
-                self.target_color.r,
-                self.target_color.g,
-                self.target_color.b,
-            );
-            let mut bg_color = LinSrgb::from_color(bg_color);
-            let target_color = LinSrgb::from_color(target_color);
-            bg_color = bg_color.mix(target_color, self.mix_factor);
-            let bg_color = Srgb::from_color(bg_color);
-            self.color = Color::from_rgb(bg_color.red, bg_color.green, bg_color.blue);
-            self.mix_factor += MIX_RATE;
+        }
+    }
+}
+
+
+/// Color space that a `ColorTransition` interpolates in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum InterpolationSpace {
+    /// Linear RGB. Transitions between complementary colors pass through gray.
+    #[default]
+    LinSrgb,
+    /// Perceptually uniform, so lightness changes evenly, but complementary colors still meet
+    /// in a desaturated middle
+    Oklab,
+    /// Oklab's lightness, chroma and hue, going the shorter way around the hue wheel, which
+    /// keeps midpoints saturated
+    Oklch,
+    /// Going the shorter way around the hue wheel
+    Hsv,
+}
+
+impl InterpolationSpace {
+    pub const ALL: [InterpolationSpace; 4] = [
+        InterpolationSpace::LinSrgb,
+        InterpolationSpace::Oklab,
+        InterpolationSpace::Oklch,
+        InterpolationSpace::Hsv,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InterpolationSpace::LinSrgb => "Linear RGB",
+            InterpolationSpace::Oklab => "Oklab",
+            InterpolationSpace::Oklch => "Oklch",
+            InterpolationSpace::Hsv => "HSV",
+        }
+    }
+
+    /// Mixes the colors in this space. `factor` may be outside of 0.0..=1.0 for easings that
+    /// overshoot, in which case the result is clamped to valid colors.
+    pub fn mix(&self, from: Color, to: Color, factor: f32) -> Color {
+        let from = Srgb::new(from.r, from.g, from.b);
+        let to = Srgb::new(to.r, to.g, to.b);
+        let mixed = match self {
+            InterpolationSpace::LinSrgb => {
+                let from = LinSrgb::from_color(from);
+                let to = LinSrgb::from_color(to);
+                Srgb::from_color(from + (to - from) * factor)
+            }
+            InterpolationSpace::Oklab => {
+                let from = Oklab::from_color(from);
+                let to = Oklab::from_color(to);
+                Srgb::from_color(from + (to - from) * factor)
+            }
+            InterpolationSpace::Oklch => {
+                let from = Oklch::from_color(from);
+                let to = Oklch::from_color(to);
+                let (from_hue, to_hue) = hues_for_mixing(
+                    from.hue.into_degrees(),
+                    from.chroma,
+                    to.hue.into_degrees(),
+                    to.chroma,
+                );
+                Srgb::from_color(Oklch::new(
+                    lerp(from.l, to.l, factor).max(0.0),
+                    lerp(from.chroma, to.chroma, factor).max(0.0),
+                    mix_hue(from_hue, to_hue, factor),
+                ))
+            }
+            InterpolationSpace::Hsv => {
+                let from = Hsv::from_color(from);
+                let to = Hsv::from_color(to);
+                let (from_hue, to_hue) = hues_for_mixing(
+                    from.hue.into_degrees(),
+                    from.saturation,
+                    to.hue.into_degrees(),
+                    to.saturation,
+                );
+                Srgb::from_color(Hsv::new(
+                    mix_hue(from_hue, to_hue, factor),
+                    lerp(from.saturation, to.saturation, factor).clamp(0.0, 1.0),
+                    lerp(from.value, to.value, factor).clamp(0.0, 1.0),
+                ))
+            }
+        };
+        Color::from_rgb(
+            mixed.red.clamp(0.0, 1.0),
+            mixed.green.clamp(0.0, 1.0),
+            mixed.blue.clamp(0.0, 1.0),
+        )
+    }
+}
+
+
+fn lerp(from: f32, to: f32, factor: f32) -> f32 {
+    from + (to - from) * factor
+}
+
+/// Grays have no meaningful hue, so a gray takes the hue of the other color instead of
+/// transitioning through unrelated hues
+fn hues_for_mixing(from_hue: f32, from_chroma: f32, to_hue: f32, to_chroma: f32) -> (f32, f32) {
+    const ACHROMATIC: f32 = 1e-4;
+    if from_chroma < ACHROMATIC {
+        (to_hue, to_hue)
+    } else if to_chroma < ACHROMATIC {
+        (from_hue, from_hue)
+    } else {
+        (from_hue, to_hue)
+    }
+}
+
+/// Mixes hues in degrees the shorter way around the hue wheel
+fn mix_hue(from: f32, to: f32, factor: f32) -> f32 {
+    let difference = (to - from + 540.0).rem_euclid(360.0) - 180.0;
+    (from + difference * factor).rem_euclid(360.0)
+}
+
+
+/// A color at a point of a `ColorTimeline`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub color: Color,
+    /// Seconds from the start of the timeline
+    pub time: f32,
+    /// Easing of the transition from the previous keyframe to this one
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    pub fn new(color: Color, time: f32, easing: Easing) -> Self {
+        Self {
+            color,
+            time,
+            easing,
+        }
+    }
+}
+
+
+/// What a `ColorTimeline` does after its last keyframe
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Repeat {
+    /// Stays on the last keyframe
+    #[default]
+    Hold,
+    /// Starts over from the first keyframe. Timelines that should loop smoothly end on the
+    /// color they start with.
+    Loop,
+    /// Plays backwards to the first keyframe, then forwards again
+    PingPong,
+}
+
+
+/// Animates a color through a sequence of keyframes, driven by elapsed seconds like
+/// `ColorTransition`
+#[derive(Clone, Default)]
+pub struct ColorTimeline {
+    keyframes: Vec<Keyframe>,
+    pub repeat: Repeat,
+    pub space: InterpolationSpace,
+    elapsed: f32,
+}
+
+impl ColorTimeline {
+    /// The keyframes are sorted by time
+    pub fn new(mut keyframes: Vec<Keyframe>, repeat: Repeat) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            keyframes,
+            repeat,
+            space: InterpolationSpace::default(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Seconds since the start of the timeline, or of the current cycle when repeating
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Whether a `Hold` timeline has reached its last keyframe. Repeating timelines never
+    /// finish.
+    pub fn is_finished(&self) -> bool {
+        self.repeat == Repeat::Hold && self.elapsed >= self.duration()
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    /// Advances the timeline by `delta` seconds. Returns `true` on the step that reaches the
+    /// last keyframe of a `Hold` timeline, or completes a cycle of a repeating one.
+    pub fn step(&mut self, delta: f32) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.elapsed += delta.max(0.0);
+        let cycle = match self.repeat {
+            Repeat::Hold => return self.elapsed >= self.duration(),
+            Repeat::Loop => self.duration(),
+            Repeat::PingPong => self.duration() * 2.0,
+        };
+        if cycle > 0.0 && self.elapsed >= cycle {
+            self.elapsed = self.elapsed.rem_euclid(cycle);
+            true
         } else {
-            self.mix_factor = STARTING_MIX_FACTOR;
-            self.transitioning = false;
+            false
+        }
+    }
+
+    /// The current color. An empty timeline is white, like a default `ColorTransition`.
+    pub fn color(&self) -> Color {
+        self.color_at(self.elapsed)
+    }
+
+    /// The color `time` seconds from the start, taking `repeat` into account
+    pub fn color_at(&self, time: f32) -> Color {
+        let duration = self.duration();
+        let time = match self.repeat {
+            _ if duration <= 0.0 => 0.0,
+            Repeat::Hold => time.clamp(0.0, duration),
+            Repeat::Loop => time.rem_euclid(duration),
+            Repeat::PingPong => {
+                let time = time.rem_euclid(duration * 2.0);
+                if time > duration {
+                    duration * 2.0 - time
+                } else {
+                    time
+                }
+            }
+        };
+        match self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time >= time)
+        {
+            None => self
+                .keyframes
+                .last()
+                .map_or(Color::WHITE, |keyframe| keyframe.color),
+            Some(0) => self.keyframes[0].color,
+            Some(i) => {
+                let from = &self.keyframes[i - 1];
+                let to = &self.keyframes[i];
+                let span = to.time - from.time;
+                if time >= to.time || span <= 0.0 {
+                    // Exactly the keyframe's color, which mixing might not round trip to
+                    return to.color;
+                }
+                let factor = (time - from.time) / span;
+                self.space
+                    .mix(from.color, to.color, to.easing.apply(factor))
+            }
+        }
+    }
+}
+
+
+/// How the background color is derived from a paragraph's emotions. Saved by label, which is
+/// how choices were saved before they were typed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMethod {
+    /// The color of the top emotion
+    #[default]
+    #[serde(rename = "Simple Color")]
+    Simple,
+    #[serde(rename = "Weighted Blend")]
+    WeightedBlend,
+    #[serde(rename = "Top 3 Blend")]
+    Top3Blend,
+    #[serde(rename = "Entropy Blend")]
+    EntropyBlend,
+    /// Black or white by sentiment, gray when mixed
+    #[serde(rename = "Black, White, Gray")]
+    BlackWhiteGray,
+    #[serde(rename = "Grayscale")]
+    Grayscale,
+}
+
+impl ColorMethod {
+    pub const ALL: [ColorMethod; 6] = [
+        ColorMethod::Simple,
+        ColorMethod::WeightedBlend,
+        ColorMethod::Top3Blend,
+        ColorMethod::EntropyBlend,
+        ColorMethod::BlackWhiteGray,
+        ColorMethod::Grayscale,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorMethod::Simple => "Simple Color",
+            ColorMethod::WeightedBlend => "Weighted Blend",
+            ColorMethod::Top3Blend => "Top 3 Blend",
+            ColorMethod::EntropyBlend => "Entropy Blend",
+            ColorMethod::BlackWhiteGray => "Black, White, Gray",
+            ColorMethod::Grayscale => "Grayscale",
+        }
+    }
+
+    /// The blend behind the color, for the methods that blend emotions
+    pub fn blend_strategy(&self) -> Option<BlendStrategy> {
+        match self {
+            ColorMethod::WeightedBlend => Some(BlendStrategy::Weighted),
+            ColorMethod::Top3Blend => Some(BlendStrategy::TopK(3)),
+            ColorMethod::EntropyBlend => Some(BlendStrategy::EntropyAware),
+            _ => None,
+        }
+    }
+
+    pub fn color(&self, model: &TopEmotionsModel, color_mapping: &ColorMapping) -> Color {
+        if let Some(strategy) = self.blend_strategy() {
+            return model.get_blended_color(color_mapping, strategy);
+        }
+        match self {
+            ColorMethod::BlackWhiteGray => model.get_black_or_white(),
+            ColorMethod::Grayscale => model.get_grayscale(),
+            _ => model.get_simple_color(color_mapping),
         }
     }
 }
 
+
 pub struct ColorTransitionVisualizer {
     pub model: Option<TopEmotionsModel>,
-    pub color_method: String,
+    /// Set when the current analysis could not be modeled
+    pub model_error: Option<EmotionModelError>,
+    pub color_method: ColorMethod,
+    pub analyzer_source: AnalyzerSource,
+    pub color_mapping: ColorMapping,
     pub transition: ColorTransition,
+    /// Colors the text color is chosen from
+    pub text_palette: TextPalette,
+    pub contrast_metric: ContrastMetric,
     text_color: Color,
+    text_contrast: f32,
+    pub text_backdrop: TextBackdrop,
+    /// Only kept while `text_backdrop` is `TextBackdrop::Frosted`
+    frosted_glass: Option<FrostedGlass>,
+    text_fitter: TextFitter,
     dynamic_text_color: bool,
 }
 
@@ -105,37 +474,35 @@ impl ColorTransitionVisualizer {
     pub fn new(bg_color: Color, text_color: Color, enable_dynamic_text_color: bool) -> Self {
         Self {
             model: None,
-            color_method: "Simple Color".to_string(),
-            transition: ColorTransition {
-                target_color: bg_color,
-                color: bg_color,
-                mix_factor: STARTING_MIX_FACTOR,
-                transitioning: false,
-            },
+            model_error: None,
+            color_method: ColorMethod::default(),
+            analyzer_source: AnalyzerSource::default(),
+            color_mapping: ColorMapping::PLUTCHIK,
+            transition: ColorTransition::new(bg_color),
+            text_palette: TextPalette::default(),
+            contrast_metric: ContrastMetric::default(),
             text_color: text_color,
+            text_contrast: 0.0,
+            text_backdrop: TextBackdrop::default(),
+            frosted_glass: None,
+            text_fitter: TextFitter::default(),
             dynamic_text_color: enable_dynamic_text_color,
         }
     }
 
-    pub fn get_options() -> HashMap<String, Vec<String>> {
-        let mut options = HashMap::new();
-        options.insert(
-            "Color Method".to_string(),
-            vec![
-                "Simple Color".to_string(),
-                "Black, White, Gray".to_string(),
-                "Grayscale".to_string(),
-            ],
-        );
-        options
-    }
-
     pub fn update_text_color(&mut self) {
-        if self.dynamic_text_color {
-            self.text_color = get_optimal_text_color(&self.transition.color);
+        let bg_color = if self.dynamic_text_color {
+            self.transition.color
         } else {
-            self.text_color = get_optimal_text_color(&self.transition.target_color);
-        }
+            self.transition.target_color
+        };
+        let choice = choose_text_color(
+            &[bg_color],
+            self.text_palette.colors(),
+            self.contrast_metric,
+        );
+        self.text_color = choice.color;
+        self.text_contrast = choice.contrast;
     }
 }
 
@@ -146,10 +513,9 @@ impl EmoVisualizer for ColorTransitionVisualizer {
 
     fn reset(&mut self, bg_color: Color, text_color: Color, enable_dynamic_text_color: bool) {
         self.model = None;
-        self.transition.color = bg_color;
+        self.model_error = None;
         self.transition.target_color = bg_color;
-        self.transition.mix_factor = STARTING_MIX_FACTOR;
-        self.transition.transitioning = false;
+        self.transition.immediate();
         self.text_color = text_color;
         self.dynamic_text_color = enable_dynamic_text_color;
     }
@@ -164,27 +530,91 @@ impl EmoVisualizer for ColorTransitionVisualizer {
     }
 
     fn update_model(&mut self, analysis: &EmocatTextAnalysis) {
-        let model = TopEmotionsModel::from_analysis(&analysis);
-        match self.color_method.as_str() {
-            "Simple Color" => self.transition.target_color = model.get_simple_color(),
-            "Black, White, Gray" => self.transition.target_color = model.get_black_or_white(),
-            "Grayscale" => self.transition.target_color = model.get_grayscale(),
-            _ => {}
-        }
+        let model = match TopEmotionsModel::try_from_source(&analysis, self.analyzer_source) {
+            Ok(model) => model,
+            Err(err) => {
+                log::error!("Paragraph {}: {}", analysis.meta.paragraph, err);
+                self.transition.target_color = MODEL_ERROR_COLOR;
+                self.model = None;
+                self.model_error = Some(err);
+                return;
+            }
+        };
+        self.transition.target_color = self.color_method.color(&model, &self.color_mapping);
         self.model = Some(model);
+        self.model_error = None;
     }
 
-    fn update_visualization(&mut self) {
-        self.transition.step();
+    fn update_visualization(&mut self, delta: f32) {
+        self.transition.step(delta);
         self.update_text_color();
     }
 
+    fn set_color_mapping(&mut self, color_mapping: ColorMapping) {
+        self.color_mapping = color_mapping;
+    }
+
+    fn snapshot(&self) -> VisualizerSnapshot {
+        VisualizerSnapshot::new(
+            self.transition.color,
+            self.transition.target_color,
+            self.text_color,
+        )
+    }
+
+    fn options(&mut self) -> Vec<VisualizerOption<'_>> {
+        let mut options = vec![VisualizerOption::choice(
+            "color_method",
+            "Color Method",
+            &mut self.color_method,
+        )];
+        options.extend(common_options(
+            &mut self.text_backdrop,
+            &mut self.transition,
+            &mut self.contrast_metric,
+            &mut self.text_palette,
+            &mut self.analyzer_source,
+        ));
+        options
+    }
+
+    fn is_transition_complete(&self) -> bool {
+        self.transition.is_complete()
+    }
+
+    fn get_analyzer_source(&self) -> AnalyzerSource {
+        self.analyzer_source
+    }
+
+    fn get_blend_strategy(&self) -> BlendStrategy {
+        self.color_method
+            .blend_strategy()
+            .unwrap_or(BlendStrategy::Weighted)
+    }
+
     fn get_text_color(&self) -> Color {
         self.text_color
     }
 
-    fn draw(&mut self, _app: &mut App, _gfx: &mut Graphics, draw: &mut Draw) {
+    fn get_text_contrast(&self) -> f32 {
+        self.text_contrast
+    }
+
+    fn get_text_backdrop(&self) -> TextBackdrop {
+        self.text_backdrop
+    }
+
+    fn get_frosted_glass(&self) -> Option<&FrostedGlass> {
+        self.frosted_glass.as_ref()
+    }
+
+    fn get_text_fitter(&mut self) -> &mut TextFitter {
+        &mut self.text_fitter
+    }
+
+    fn draw(&mut self, _app: &mut App, gfx: &mut Graphics, draw: &mut Draw) {
         // The following call to clear() is important when rendering draw & egui output together.
         draw.clear(self.transition.color);
+        update_frosted_glass(&mut self.frosted_glass, self.text_backdrop, gfx, draw);
     }
 }
@@ -1,19 +1,23 @@
-use super::color_transition::ColorTransition;
-use super::{get_optimal_text_color, get_optimal_text_size, EmoVisualizer, VisualizerSelection};
-use crate::emotion::{ColorMapping, EmoColor, EmocatTextAnalysis, Sentiment, TopEmotionsModel};
-use crate::utils::{get_rng, scale_font};
+use super::super::harness::{hex, VisualizerSnapshot};
+use super::super::options::VisualizerOption;
+use super::backdrop::{update_frosted_glass, FrostedGlass, TextBackdrop};
+use super::color_transition::{ColorTimeline, ColorTransition};
+use super::{
+    choose_text_color, common_options, overlaps_paragraph_area, tile_timeline, EmoVisualizer,
+    TextPalette, Tile, VisualizerSelection, MODEL_ERROR_COLOR,
+};
+use crate::colors::contrast::{composite, ContrastMetric};
+use crate::emotion::{
+    AnalyzerSource, ColorMapping, EmocatTextAnalysis, EmotionModelError, TopEmotionsModel,
+};
+use crate::textutils::TextFitter;
+use crate::utils::get_rng;
 use notan::draw::*;
 use notan::log;
 use notan::math::{vec2, Vec2};
 use notan::prelude::*;
-use palette::{Darken, FromColor, Lighten, Srgb};
-use std::collections::HashMap;
 
 
-/// Slightly increases the sentiment score for use as a value to brighten/darken HSV
-const VALUE_MODIFIER: f32 = 3.0;
-const MINIMAL_ENHANCEMENT: f32 = 0.05;
-// const MINIMAL_ENHANCEMENT: f32 = 0.1;
 const TILE_ALPHA: f32 = 0.3;
 // const TILE_ALPHA: f32 = 0.5;
 // const TILE_ALPHA: f32 = 1.0;
@@ -21,38 +25,20 @@ const MAX_COLS: usize = 5;
 const MAX_ROWS: usize = 5;
 
 
-/// Represents different Tile "baselines/archetypes"
-///
-/// For now just contains an EmoColor, but anticipating expansion
-struct Tile {
-    emocolor: EmoColor,
-}
-
-
-impl Tile {
-    fn new(emocolor: &EmoColor) -> Self {
-        Self {
-            emocolor: EmoColor {
-                emotion: emocolor.emotion.clone(),
-                hsv: emocolor.hsv,
-                sentiment: emocolor.sentiment.clone(),
-            },
-        }
-    }
-}
-
-
 pub struct TilesLayout {
     tile_size: Vec2,
+    /// Size of the area covered by the tiles
+    work_size: Vec2,
     rows: usize,
     cols: usize,
-    reprs: Vec<Vec<ColorTransition>>,
+    reprs: Vec<Vec<ColorTimeline>>,
 }
 
 impl TilesLayout {
     fn none() -> Self {
         Self {
             tile_size: vec2(0.0, 0.0),
+            work_size: vec2(0.0, 0.0),
             rows: 0,
             cols: 0,
             reprs: vec![],
@@ -64,108 +50,117 @@ impl TilesLayout {
 pub struct TilesVisualizer {
     rng: Random,
     pub model: Option<TopEmotionsModel>,
+    /// Set when the current analysis could not be modeled
+    pub model_error: Option<EmotionModelError>,
     pub transition: ColorTransition,
-    /// As I experiment, I want a separate property to base the optimal text color on,
-    /// because `bgcolor` above may not change per analysis (i.e. it might just remain
-    /// white or whatever color the visualizer was initialized with).
-    ///
-    /// See `update_model()` in impl for EmoVisualizer
-    bg_color_for_text: Color,
+    /// Colors the text color is chosen from
+    pub text_palette: TextPalette,
+    pub contrast_metric: ContrastMetric,
     text_color: Color,
-    pub text_shadow_style: String,
+    text_contrast: f32,
+    pub text_backdrop: TextBackdrop,
+    /// Only kept while `text_backdrop` is `TextBackdrop::Frosted`
+    frosted_glass: Option<FrostedGlass>,
+    text_fitter: TextFitter,
+    pub analyzer_source: AnalyzerSource,
+    pub color_mapping: ColorMapping,
     dynamic_text_color: bool,
-    tile_texture: Texture,
+    /// `None` when running headless, in which case nothing is drawn for the tiles
+    tile_texture: Option<Texture>,
     tiles: Vec<Tile>,
     layout: TilesLayout,
     refresh_layout: bool,
+    /// Set when the paragraph changes, so that tiles leave the previous paragraph's colors
+    /// without finishing their timelines
+    restart_tiles: bool,
 }
 
-fn get_sentiment_enhanced_color(
-    emocolor: &EmoColor,
-    rng: &mut Random,
-    positive_sentiment: f32,
-    negative_sentiment: f32,
-) -> Color {
-    let mut hsv_color = emocolor.hsv.clone();
-    hsv_color = match emocolor.sentiment {
-        Sentiment::POSITIVE => {
-            if positive_sentiment > 0.0 {
-                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
-            } else {
-                // In some cases, with nrclex, it seems the sentiment score can be zero even if the
-                // emotion is associated to a sentiment. In such cases, use a very minimal range.
-                //
-                // @TODO: Investigate whether there is a bug/error in the emocat nrclex adapter
-                hsv_color.lighten(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
-            }
-        }
-        Sentiment::NEGATIVE => {
-            if negative_sentiment > 0.0 {
-                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
-            } else {
-                // See comment in positive sentiment arm above
-                hsv_color.darken(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
-            }
-        }
-        _ => {
-            if positive_sentiment > negative_sentiment {
-                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
-            } else if negative_sentiment > positive_sentiment {
-                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
-            } else {
-                hsv_color
-            }
-        }
-    };
-    let srgb = Srgb::from_color(hsv_color);
-    Color::from_rgb(srgb.red, srgb.green, srgb.blue)
-}
-
-
 impl TilesVisualizer {
     pub fn new(
         bg_color: Color,
         text_color: Color,
         enable_dynamic_text_color: bool,
         tile_texture: Texture,
+    ) -> Self {
+        let mut visualizer = Self::new_headless(bg_color, text_color, enable_dynamic_text_color);
+        visualizer.tile_texture = Some(tile_texture);
+        visualizer
+    }
+
+    /// A visualizer without a tile texture, for running without graphics, see
+    /// `emotion_bg_visualizer::harness`
+    pub fn new_headless(
+        bg_color: Color,
+        text_color: Color,
+        enable_dynamic_text_color: bool,
     ) -> Self {
         let (rng, _) = get_rng(None);
         Self {
             rng: rng,
             model: None,
-            transition: ColorTransition {
-                target_color: bg_color,
-                color: bg_color,
-                ..Default::default()
-            },
-            bg_color_for_text: bg_color,
+            model_error: None,
+            transition: ColorTransition::new(bg_color),
+            text_palette: TextPalette::default(),
+            contrast_metric: ContrastMetric::default(),
             text_color: text_color,
-            text_shadow_style: "None".to_string(),
+            text_contrast: 0.0,
+            text_backdrop: TextBackdrop::default(),
+            frosted_glass: None,
+            text_fitter: TextFitter::default(),
+            analyzer_source: AnalyzerSource::default(),
+            color_mapping: ColorMapping::PLUTCHIK,
             dynamic_text_color: enable_dynamic_text_color,
-            tile_texture: tile_texture,
+            tile_texture: None,
             tiles: vec![],
             layout: TilesLayout::none(),
             refresh_layout: false,
+            restart_tiles: false,
         }
     }
 
-    pub fn get_options() -> HashMap<String, Vec<String>> {
-        let mut options = HashMap::new();
-        options.insert(
-            "Shadow Style".to_string(),
-            vec![
-                "None".to_string(),
-                "Moderate".to_string(),
-                "Full".to_string(),
-            ],
+    /// Chooses the text color with the best contrast on all of the colors behind the paragraph
+    pub fn update_text_color(&mut self) {
+        let choice = choose_text_color(
+            &self.get_colors_behind_paragraph(),
+            self.text_palette.colors(),
+            self.contrast_metric,
         );
-        options
+        self.text_color = choice.color;
+        self.text_contrast = choice.contrast;
     }
 
 
-    pub fn update_text_color(&mut self) {
-        // self.text_color = get_optimal_text_color(&self.bg_color);
-        self.text_color = get_optimal_text_color(&self.bg_color_for_text);
+    /// The background the text color is chosen on: the current color of the transition
+    /// with dynamic text color, otherwise the color it transitions to
+    fn get_bg_color_for_text(&self) -> Color {
+        if self.dynamic_text_color {
+            self.transition.color
+        } else {
+            self.transition.target_color
+        }
+    }
+
+
+    /// The colors behind the paragraph: the tiles under the paragraph blended over the
+    /// background, or the background when there are no tiles
+    fn get_colors_behind_paragraph(&self) -> Vec<Color> {
+        let bg_color = self.get_bg_color_for_text();
+        let mut colors = vec![];
+        for (row_index, row) in self.layout.reprs.iter().enumerate() {
+            for (col_index, col) in row.iter().enumerate() {
+                let tile_pos = vec2(
+                    col_index as f32 * self.layout.tile_size.x,
+                    row_index as f32 * self.layout.tile_size.y,
+                );
+                if overlaps_paragraph_area(tile_pos, self.layout.tile_size, self.layout.work_size) {
+                    colors.push(composite(&col.color(), TILE_ALPHA, &bg_color));
+                }
+            }
+        }
+        if colors.is_empty() {
+            colors.push(bg_color);
+        }
+        colors
     }
 
 
@@ -178,16 +173,16 @@ impl TilesVisualizer {
                 if col >= reprs_cols {
                     let first_row_len = self.layout.reprs[0].len();
                     if first_row_len == 0 {
-                        self.layout.reprs[row].push(ColorTransition::default());
+                        self.layout.reprs[row].push(ColorTimeline::default());
                     } else {
-                        let transition_clone: ColorTransition;
+                        let timeline_clone: ColorTimeline;
                         if first_row_len == 1 {
-                            transition_clone = self.layout.reprs[0][0].clone();
+                            timeline_clone = self.layout.reprs[0][0].clone();
                         } else {
                             let selection = self.rng.random_range(0..first_row_len);
-                            transition_clone = self.layout.reprs[0][selection].clone();
+                            timeline_clone = self.layout.reprs[0][selection].clone();
                         }
-                        self.layout.reprs[row].push(transition_clone);
+                        self.layout.reprs[row].push(timeline_clone);
                     }
                 }
             }
@@ -208,7 +203,8 @@ impl TilesVisualizer {
         }
     }
 
-    fn prepare_layout(&mut self, draw: &mut Draw) {
+    fn prepare_layout(&mut self, work_size: Vec2) {
+        self.layout.work_size = work_size;
         if self.refresh_layout {
             if self.tiles.len() > MAX_COLS {
                 self.layout.cols = self.tiles.len();
@@ -217,8 +213,8 @@ impl TilesVisualizer {
             }
             self.layout.rows = self.rng.random_range(1..=MAX_ROWS);
             self.layout.tile_size = vec2(
-                draw.width() / self.layout.cols as f32,
-                draw.height() / self.layout.rows as f32,
+                work_size.x / self.layout.cols as f32,
+                work_size.y / self.layout.rows as f32,
             );
             log::debug!(
                 "refreshed: rows {}, cols {}",
@@ -228,39 +224,46 @@ impl TilesVisualizer {
             self.grow_or_shrink_layout();
             self.refresh_layout = false;
         } else {
-            self.layout.cols = (draw.width() / self.layout.tile_size.x).ceil() as usize;
-            self.layout.rows = (draw.height() / self.layout.tile_size.y).ceil() as usize;
+            self.layout.cols = (work_size.x / self.layout.tile_size.x).ceil() as usize;
+            self.layout.rows = (work_size.y / self.layout.tile_size.y).ceil() as usize;
         }
     }
 
-    fn draw_tiles_grid(&mut self, draw: &mut Draw) {
-        if self.tiles.len() < 1 {
+    /// Steps the tile timelines by `delta` seconds, and starts a new timeline for tiles that
+    /// finished theirs, or all of them when the paragraph changed
+    fn step_tiles(&mut self, delta: f32) {
+        if self.tiles.is_empty() {
             return;
         }
-        self.prepare_layout(draw);
-
-        for (row_index, row) in self.layout.reprs.iter_mut().enumerate() {
-            for (col_index, col) in row.iter_mut().enumerate() {
-                if col.transitioning {
-                    // col.immediate();
-                    col.step();
-                } else {
-                    let lucky_tile;
-                    if self.tiles.len() > 1 {
-                        lucky_tile = self.rng.random_range(0..self.tiles.len());
-                    } else {
-                        lucky_tile = 0;
-                    }
-                    let fill_color = get_sentiment_enhanced_color(
-                        &self.tiles[lucky_tile].emocolor,
+        let model = self.model.as_ref().unwrap();
+        for row in self.layout.reprs.iter_mut() {
+            for col in row.iter_mut() {
+                if self.restart_tiles || col.is_finished() {
+                    *col = tile_timeline(
+                        col.color(),
+                        &self.tiles,
+                        model,
+                        &self.transition,
                         &mut self.rng,
-                        self.model.as_ref().unwrap().positive,
-                        self.model.as_ref().unwrap().negative,
                     );
-                    col.target_color = fill_color;
-                    col.transitioning = true;
+                } else {
+                    col.step(delta);
                 }
+            }
+        }
+        self.restart_tiles = false;
+    }
 
+    fn draw_tiles_grid(&mut self, draw: &mut Draw) {
+        if self.tiles.len() < 1 {
+            return;
+        }
+        let Some(tile_texture) = &self.tile_texture else {
+            return;
+        };
+
+        for (row_index, row) in self.layout.reprs.iter().enumerate() {
+            for (col_index, col) in row.iter().enumerate() {
                 // draw.rect(
                 //     (
                 //         col_index as f32 * self.layout.tile_size.x,
@@ -272,7 +275,7 @@ impl TilesVisualizer {
                 // .alpha(TILE_ALPHA)
                 // .fill_color(col.color)
                 // .fill();
-                draw.image(&self.tile_texture.as_ref())
+                draw.image(tile_texture)
                     .position(
                         col_index as f32 * self.layout.tile_size.x,
                         row_index as f32 * self.layout.tile_size.y,
@@ -280,7 +283,7 @@ impl TilesVisualizer {
                     .size(self.layout.tile_size.x, self.layout.tile_size.y)
                     .alpha_mode(BlendMode::OVER)
                     .alpha(TILE_ALPHA)
-                    .color(col.color);
+                    .color(col.color());
             }
         }
     }
@@ -295,16 +298,15 @@ impl EmoVisualizer for TilesVisualizer {
 
     fn reset(&mut self, bg_color: Color, text_color: Color, enable_dynamic_text_color: bool) {
         self.model = None;
-        self.transition.color = bg_color;
+        self.model_error = None;
         self.transition.target_color = bg_color;
-        self.transition.mix_factor = 0.0;
-        self.transition.transitioning = false;
-        self.bg_color_for_text = bg_color;
+        self.transition.immediate();
         self.text_color = text_color;
         self.dynamic_text_color = enable_dynamic_text_color;
         self.tiles = vec![];
         self.layout = TilesLayout::none();
         self.refresh_layout = false;
+        self.restart_tiles = false;
     }
 
     fn gracefully_reset(
@@ -319,116 +321,114 @@ impl EmoVisualizer for TilesVisualizer {
         self.tiles = vec![];
         self.layout = TilesLayout::none();
         self.refresh_layout = false;
+        self.restart_tiles = false;
     }
 
 
     fn update_model(&mut self, analysis: &EmocatTextAnalysis) {
-        let model = TopEmotionsModel::from_analysis(&analysis);
-        let top_emocolors = model.get_top_emocolors(&ColorMapping::PLUTCHIK);
-        self.tiles = top_emocolors
-            .iter()
-            .map(|emocolor| Tile::new(emocolor))
-            .collect();
-        self.transition.target_color = model.get_simple_color();
-        self.bg_color_for_text = self.transition.target_color;
+        let model = match TopEmotionsModel::try_from_source(&analysis, self.analyzer_source) {
+            Ok(model) => model,
+            Err(err) => {
+                log::error!("Paragraph {}: {}", analysis.meta.paragraph, err);
+                // Without a model there is nothing to tile, so fall back to a plain background
+                self.tiles = vec![];
+                self.layout = TilesLayout::none();
+                self.refresh_layout = false;
+                self.transition.target_color = MODEL_ERROR_COLOR;
+                self.model = None;
+                self.model_error = Some(err);
+                return;
+            }
+        };
+        self.tiles = Tile::from_model(&model, &self.color_mapping);
+        self.transition.target_color = model.get_simple_color(&self.color_mapping);
         self.refresh_layout = true;
+        self.restart_tiles = true;
         self.model = Some(model);
+        self.model_error = None;
     }
 
-    fn update_visualization(&mut self) {
-        self.transition.step();
+    fn update_visualization(&mut self, delta: f32) {
+        self.transition.step(delta);
+        self.step_tiles(delta);
         self.update_text_color();
     }
 
+    fn update_layout(&mut self, work_size: Vec2) {
+        if self.tiles.is_empty() {
+            return;
+        }
+        self.prepare_layout(work_size);
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        (self.rng, _) = get_rng(Some(seed));
+    }
+
+    fn snapshot(&self) -> VisualizerSnapshot {
+        let mut snapshot = VisualizerSnapshot::new(
+            self.transition.color,
+            self.transition.target_color,
+            self.text_color,
+        );
+        snapshot.tiles = self
+            .layout
+            .reprs
+            .iter()
+            .map(|row| row.iter().map(|col| hex(col.color())).collect())
+            .collect();
+        snapshot.tile_size = [self.layout.tile_size.x, self.layout.tile_size.y];
+        snapshot
+    }
+
+    fn set_color_mapping(&mut self, color_mapping: ColorMapping) {
+        self.color_mapping = color_mapping;
+    }
+
 
-    fn draw(&mut self, _app: &mut App, _gfx: &mut Graphics, draw: &mut Draw) {
+    fn draw(&mut self, _app: &mut App, gfx: &mut Graphics, draw: &mut Draw) {
         // The following call to clear() is important when rendering draw & egui output together.
         draw.clear(self.transition.color);
+        self.update_layout(vec2(draw.width(), draw.height()));
         self.draw_tiles_grid(draw);
+        update_frosted_glass(&mut self.frosted_glass, self.text_backdrop, gfx, draw);
+    }
+
+    fn options(&mut self) -> Vec<VisualizerOption<'_>> {
+        common_options(
+            &mut self.text_backdrop,
+            &mut self.transition,
+            &mut self.contrast_metric,
+            &mut self.text_palette,
+            &mut self.analyzer_source,
+        )
+    }
+
+    fn is_transition_complete(&self) -> bool {
+        self.transition.is_complete()
+    }
+
+    fn get_analyzer_source(&self) -> AnalyzerSource {
+        self.analyzer_source
     }
 
     fn get_text_color(&self) -> Color {
         self.text_color
     }
 
+    fn get_text_contrast(&self) -> f32 {
+        self.text_contrast
+    }
+
+    fn get_text_backdrop(&self) -> TextBackdrop {
+        self.text_backdrop
+    }
 
-    fn draw_paragraph(&mut self, draw: &mut Draw, font: &Font, text: &str, work_size: Vec2) {
-        let textbox_width = work_size.x * 0.75;
-        let textbox_height = work_size.y * 0.75;
-        let text_pos_x = work_size.x * 0.5 - textbox_width * 0.5;
-        let text_pos_y = work_size.y * 0.5;
-        let text_color = self.get_text_color();
-
-        let font_size = get_optimal_text_size(
-            draw,
-            font,
-            text,
-            work_size,
-            32.0,
-            textbox_width,
-            textbox_height,
-        );
+    fn get_frosted_glass(&self) -> Option<&FrostedGlass> {
+        self.frosted_glass.as_ref()
+    }
 
-        if self.text_shadow_style != "None" {
-            let offset = work_size.x * 0.0005;
-            if text_color == Color::WHITE {
-                if self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .alpha(0.25)
-                        .color(Color::BLACK)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x - offset * 3.0, text_pos_y + offset * 3.0)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-                if self.text_shadow_style == "Moderate" || self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .color(Color::BLACK)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x - offset, text_pos_y + offset)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-            } else {
-                if self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .alpha(0.3)
-                        .color(Color::WHITE)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x + offset * 3.0, text_pos_y - offset * 3.0)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-                if self.text_shadow_style == "Moderate" || self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .color(Color::WHITE)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x + offset, text_pos_y - offset)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-            }
-        }
-        draw.text(&font, &text)
-            .alpha_mode(BlendMode::OVER)
-            .color(self.get_text_color())
-            // NOTE: These draw.text fonts size differently than font sizes in egui
-            .size(scale_font(font_size, work_size))
-            .max_width(textbox_width)
-            .position(text_pos_x, text_pos_y)
-            .v_align_middle()
-            .h_align_left();
+    fn get_text_fitter(&mut self) -> &mut TextFitter {
+        &mut self.text_fitter
     }
 }
@@ -1,61 +1,52 @@
 pub mod shader;
 
 use super::super::get_work_size;
-use super::color_transition::ColorTransition;
-use super::{get_optimal_text_color, get_optimal_text_size, EmoVisualizer, VisualizerSelection};
-use crate::emotion::{ColorMapping, EmoColor, EmocatTextAnalysis, Sentiment, TopEmotionsModel};
+use super::super::harness::{hex, VisualizerSnapshot};
+use super::super::options::VisualizerOption;
+use super::backdrop::{update_frosted_glass, FrostedGlass, TextBackdrop};
+use super::color_transition::{ColorTimeline, ColorTransition};
+use super::{
+    choose_text_color, common_options, overlaps_paragraph_area, tile_timeline, EmoVisualizer,
+    TextPalette, Tile, VisualizerSelection, MODEL_ERROR_COLOR,
+};
+use crate::colors::contrast::{composite, ContrastMetric};
+use crate::emotion::{
+    AnalyzerSource, ColorMapping, EmocatTextAnalysis, EmotionModelError, TopEmotionsModel,
+};
 use crate::mathutils::get_cell_pos_in_grid;
-use crate::utils::{get_rng, scale_font};
+use crate::textutils::TextFitter;
+use crate::utils::get_rng;
 use notan::draw::*;
 use notan::log;
 use notan::math::{vec2, Vec2};
 use notan::prelude::*;
-use palette::{Darken, FromColor, Lighten, Srgb};
 use shader::{TileShaderBundle, FRAG};
-use std::collections::HashMap;
 #[cfg(target_arch = "wasm32")]
 use web_sys::window;
 
-/// Slightly increases the sentiment score for use as a value to brighten/darken HSV
-const VALUE_MODIFIER: f32 = 3.0;
-const MINIMAL_ENHANCEMENT: f32 = 0.05;
-// const MINIMAL_ENHANCEMENT: f32 = 0.1;
 // const TILE_ALPHA: f32 = 0.3;
 // const TILE_ALPHA: f32 = 0.5;
 // const TILE_ALPHA: f32 = 1.0;
 const MAX_COLS: usize = 5;
 const MAX_ROWS: usize = 5;
-
-/// Represents different Tile "baselines/archetypes"
-///
-/// For now just contains an EmoColor, but anticipating expansion
-struct Tile {
-    emocolor: EmoColor,
-}
-
-impl Tile {
-    fn new(emocolor: &EmoColor) -> Self {
-        Self {
-            emocolor: EmoColor {
-                emotion: emocolor.emotion.clone(),
-                hsv: emocolor.hsv,
-                sentiment: emocolor.sentiment.clone(),
-            },
-        }
-    }
-}
+/// Share of the tile color in the center of a tile, where the shader in `emo_tile.frag.glsl`
+/// mixes it most with the background
+const TILE_CENTER_MIX: f32 = 0.6;
 
 pub struct TilesLayout {
     tile_size: Vec2,
+    /// Size of the area covered by the tiles
+    work_size: Vec2,
     rows: usize,
     cols: usize,
-    reprs: Vec<Vec<ColorTransition>>,
+    reprs: Vec<Vec<ColorTimeline>>,
 }
 
 impl TilesLayout {
     fn none() -> Self {
         Self {
             tile_size: vec2(0.0, 0.0),
+            work_size: vec2(0.0, 0.0),
             rows: 0,
             cols: 0,
             reprs: vec![],
@@ -63,6 +54,15 @@ impl TilesLayout {
     }
 }
 
+/// Creates the pipeline for the tile shader, logging the error when it fails so that the
+/// visualizer keeps running without tiles
+fn create_tile_pipeline(gfx: &mut Graphics) -> Result<Pipeline, String> {
+    create_shape_pipeline(gfx, Some(&FRAG)).map_err(|err| {
+        log::error!("Could not create the tile shader pipeline: {}", err);
+        err
+    })
+}
+
 pub struct ShaderBundleStore {
     bundles: Vec<TileShaderBundle>,
 }
@@ -102,62 +102,34 @@ impl ShaderBundleStore {
 pub struct TiledShadersVisualizer {
     rng: Random,
     pub model: Option<TopEmotionsModel>,
+    /// Set when the current analysis could not be modeled
+    pub model_error: Option<EmotionModelError>,
     pub transition: ColorTransition,
-    /// As I experiment, I want a separate property to base the optimal text color on,
-    /// because `bgcolor` above may not change per analysis (i.e. it might just remain
-    /// white or whatever color the visualizer was initialized with).
-    ///
-    /// See `update_model()` in impl for EmoVisualizer
-    bg_color_for_text: Color,
+    /// Colors the text color is chosen from
+    pub text_palette: TextPalette,
+    pub contrast_metric: ContrastMetric,
     text_color: Color,
-    pub text_shadow_style: String,
+    text_contrast: f32,
+    pub text_backdrop: TextBackdrop,
+    /// Only kept while `text_backdrop` is `TextBackdrop::Frosted`
+    frosted_glass: Option<FrostedGlass>,
+    text_fitter: TextFitter,
+    pub analyzer_source: AnalyzerSource,
+    pub color_mapping: ColorMapping,
     dynamic_text_color: bool,
     tiles: Vec<Tile>,
     layout: TilesLayout,
-    shader_pipeline: Pipeline,
+    /// `None` when running headless, until the first `draw()`. Creating the pipeline is only
+    /// tried once, and the tiles are not drawn when it failed.
+    shader_pipeline: Option<Result<Pipeline, String>>,
     shader_bundles: ShaderBundleStore,
     refresh_layout: bool,
-}
-
-fn get_sentiment_enhanced_color(
-    emocolor: &EmoColor,
-    rng: &mut Random,
-    positive_sentiment: f32,
-    negative_sentiment: f32,
-) -> Color {
-    let mut hsv_color = emocolor.hsv.clone();
-    hsv_color = match emocolor.sentiment {
-        Sentiment::POSITIVE => {
-            if positive_sentiment > 0.0 {
-                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
-            } else {
-                // In some cases, with nrclex, it seems the sentiment score can be zero even if the
-                // emotion is associated to a sentiment. In such cases, use a very minimal range.
-                //
-                // @TODO: Investigate whether there is a bug/error in the emocat nrclex adapter
-                hsv_color.lighten(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
-            }
-        }
-        Sentiment::NEGATIVE => {
-            if negative_sentiment > 0.0 {
-                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
-            } else {
-                // See comment in positive sentiment arm above
-                hsv_color.darken(rng.random_range(0.0..(MINIMAL_ENHANCEMENT * VALUE_MODIFIER)))
-            }
-        }
-        _ => {
-            if positive_sentiment > negative_sentiment {
-                hsv_color.lighten(rng.random_range(0.0..(positive_sentiment * VALUE_MODIFIER)))
-            } else if negative_sentiment > positive_sentiment {
-                hsv_color.darken(rng.random_range(0.0..(negative_sentiment * VALUE_MODIFIER)))
-            } else {
-                hsv_color
-            }
-        }
-    };
-    let srgb = Srgb::from_color(hsv_color);
-    Color::from_rgb(srgb.red, srgb.green, srgb.blue)
+    /// Set when the layout is refreshed, so that the next `draw()` adds shader bundles for any
+    /// new tiles
+    grow_shader_bundles: bool,
+    /// Set when the paragraph changes, so that tiles leave the previous paragraph's colors
+    /// without finishing their timelines
+    restart_tiles: bool,
 }
 
 impl TiledShadersVisualizer {
@@ -166,46 +138,85 @@ impl TiledShadersVisualizer {
         bg_color: Color,
         text_color: Color,
         enable_dynamic_text_color: bool,
+    ) -> Self {
+        let mut visualizer = Self::new_headless(bg_color, text_color, enable_dynamic_text_color);
+        visualizer.shader_pipeline = Some(create_tile_pipeline(gfx));
+        visualizer.shader_bundles = ShaderBundleStore::new(gfx, MAX_COLS * MAX_ROWS);
+        visualizer
+    }
+
+    /// A visualizer without a shader pipeline or shader bundles, for running without graphics,
+    /// see `emotion_bg_visualizer::harness`. They are created on the first `draw()`.
+    pub fn new_headless(
+        bg_color: Color,
+        text_color: Color,
+        enable_dynamic_text_color: bool,
     ) -> Self {
         let (rng, _) = get_rng(None);
-        let shader_pipeline = create_shape_pipeline(gfx, Some(&FRAG)).unwrap();
 
         Self {
             rng: rng,
             model: None,
-            transition: ColorTransition {
-                target_color: bg_color,
-                color: bg_color,
-                ..Default::default()
-            },
-            bg_color_for_text: bg_color,
+            model_error: None,
+            transition: ColorTransition::new(bg_color),
+            text_palette: TextPalette::default(),
+            contrast_metric: ContrastMetric::default(),
             text_color: text_color,
-            text_shadow_style: "None".to_string(),
+            text_contrast: 0.0,
+            text_backdrop: TextBackdrop::default(),
+            frosted_glass: None,
+            text_fitter: TextFitter::default(),
+            analyzer_source: AnalyzerSource::default(),
+            color_mapping: ColorMapping::PLUTCHIK,
             dynamic_text_color: enable_dynamic_text_color,
             tiles: vec![],
             layout: TilesLayout::none(),
-            shader_pipeline,
-            shader_bundles: ShaderBundleStore::new(gfx, MAX_COLS * MAX_ROWS),
+            shader_pipeline: None,
+            shader_bundles: ShaderBundleStore::default(),
             refresh_layout: false,
+            grow_shader_bundles: false,
+            restart_tiles: false,
         }
     }
 
-    pub fn get_options() -> HashMap<String, Vec<String>> {
-        let mut options = HashMap::new();
-        options.insert(
-            "Shadow Style".to_string(),
-            vec![
-                "None".to_string(),
-                "Moderate".to_string(),
-                "Full".to_string(),
-            ],
+    /// Chooses the text color with the best contrast on all of the colors behind the paragraph
+    pub fn update_text_color(&mut self) {
+        let choice = choose_text_color(
+            &self.get_colors_behind_paragraph(),
+            self.text_palette.colors(),
+            self.contrast_metric,
         );
-        options
+        self.text_color = choice.color;
+        self.text_contrast = choice.contrast;
     }
 
-    pub fn update_text_color(&mut self) {
-        // self.text_color = get_optimal_text_color(&self.bg_color);
-        self.text_color = get_optimal_text_color(&self.bg_color_for_text);
+    /// The background the text color is chosen on: the current color of the transition
+    /// with dynamic text color, otherwise the color it transitions to
+    fn get_bg_color_for_text(&self) -> Color {
+        if self.dynamic_text_color {
+            self.transition.color
+        } else {
+            self.transition.target_color
+        }
+    }
+
+    /// The colors behind the paragraph: the background, and the centers of the tiles under
+    /// the paragraph, where the tile color is strongest
+    fn get_colors_behind_paragraph(&self) -> Vec<Color> {
+        let bg_color = self.get_bg_color_for_text();
+        let mut colors = vec![bg_color];
+        for (row_index, row) in self.layout.reprs.iter().enumerate() {
+            for (col_index, col) in row.iter().enumerate() {
+                let tile_pos = vec2(
+                    col_index as f32 * self.layout.tile_size.x,
+                    row_index as f32 * self.layout.tile_size.y,
+                );
+                if overlaps_paragraph_area(tile_pos, self.layout.tile_size, self.layout.work_size) {
+                    colors.push(composite(&col.color(), TILE_CENTER_MIX, &bg_color));
+                }
+            }
+        }
+        colors
     }
 
     fn manage_cols_in_row(&mut self, row: usize) {
@@ -217,16 +228,16 @@ impl TiledShadersVisualizer {
                 if col >= reprs_cols {
                     let first_row_len = self.layout.reprs[0].len();
                     if first_row_len == 0 {
-                        self.layout.reprs[row].push(ColorTransition::default());
+                        self.layout.reprs[row].push(ColorTimeline::default());
                     } else {
-                        let transition_clone: ColorTransition;
+                        let timeline_clone: ColorTimeline;
                         if first_row_len == 1 {
-                            transition_clone = self.layout.reprs[0][0].clone();
+                            timeline_clone = self.layout.reprs[0][0].clone();
                         } else {
                             let selection = self.rng.random_range(0..first_row_len);
-                            transition_clone = self.layout.reprs[0][selection].clone();
+                            timeline_clone = self.layout.reprs[0][selection].clone();
                         }
-                        self.layout.reprs[row].push(transition_clone);
+                        self.layout.reprs[row].push(timeline_clone);
                     }
                 }
             }
@@ -264,7 +275,8 @@ impl TiledShadersVisualizer {
         }
     }
 
-    fn prepare_layout(&mut self, gfx: &mut Graphics, draw: &mut Draw) {
+    fn prepare_layout(&mut self, work_size: Vec2) {
+        self.layout.work_size = work_size;
         if self.refresh_layout {
             if self.tiles.len() > MAX_COLS {
                 self.layout.cols = self.tiles.len();
@@ -273,8 +285,8 @@ impl TiledShadersVisualizer {
             }
             self.layout.rows = self.rng.random_range(1..=MAX_ROWS);
             self.layout.tile_size = vec2(
-                draw.width() / self.layout.cols as f32,
-                draw.height() / self.layout.rows as f32,
+                work_size.x / self.layout.cols as f32,
+                work_size.y / self.layout.rows as f32,
             );
             log::debug!(
                 "layout refreshed: rows {}, cols {}",
@@ -282,43 +294,58 @@ impl TiledShadersVisualizer {
                 self.layout.cols
             );
             self.grow_or_shrink_layout();
-            self.grow_shader_bundles(gfx);
             self.refresh_layout = false;
+            self.grow_shader_bundles = true;
         } else {
-            self.layout.cols = (draw.width() / self.layout.tile_size.x).ceil() as usize;
-            self.layout.rows = (draw.height() / self.layout.tile_size.y).ceil() as usize;
+            self.layout.cols = (work_size.x / self.layout.tile_size.x).ceil() as usize;
+            self.layout.rows = (work_size.y / self.layout.tile_size.y).ceil() as usize;
         }
     }
 
-    fn draw_tiles_grid(&mut self, app: &mut App, gfx: &mut Graphics, draw: &mut Draw) {
-        if self.tiles.len() < 1 {
+    /// Steps the tile timelines by `delta` seconds, and starts a new timeline for tiles that
+    /// finished theirs, or all of them when the paragraph changed
+    fn step_tiles(&mut self, delta: f32) {
+        if self.tiles.is_empty() {
             return;
         }
-        self.prepare_layout(gfx, draw);
-
-        for (row_index, row) in self.layout.reprs.iter_mut().enumerate() {
-            let row_len = row.len();
-            for (col_index, col) in row.iter_mut().enumerate() {
-                if col.transitioning {
-                    // col.immediate();
-                    col.step();
-                } else {
-                    let lucky_tile;
-                    if self.tiles.len() > 1 {
-                        lucky_tile = self.rng.random_range(0..self.tiles.len());
-                    } else {
-                        lucky_tile = 0;
-                    }
-                    let fill_color = get_sentiment_enhanced_color(
-                        &self.tiles[lucky_tile].emocolor,
+        let model = self.model.as_ref().unwrap();
+        for row in self.layout.reprs.iter_mut() {
+            for col in row.iter_mut() {
+                if self.restart_tiles || col.is_finished() {
+                    *col = tile_timeline(
+                        col.color(),
+                        &self.tiles,
+                        model,
+                        &self.transition,
                         &mut self.rng,
-                        self.model.as_ref().unwrap().positive,
-                        self.model.as_ref().unwrap().negative,
                     );
-                    col.target_color = fill_color;
-                    col.transitioning = true;
+                } else {
+                    col.step(delta);
                 }
+            }
+        }
+        self.restart_tiles = false;
+    }
 
+    fn draw_tiles_grid(&mut self, app: &mut App, gfx: &mut Graphics, draw: &mut Draw) {
+        if self.tiles.len() < 1 {
+            return;
+        }
+        if self.grow_shader_bundles {
+            self.grow_shader_bundles(gfx);
+            self.grow_shader_bundles = false;
+        }
+        let shader_pipeline = match self
+            .shader_pipeline
+            .get_or_insert_with(|| create_tile_pipeline(gfx))
+        {
+            Ok(pipeline) => pipeline,
+            Err(_) => return,
+        };
+
+        for (row_index, row) in self.layout.reprs.iter().enumerate() {
+            let row_len = row.len();
+            for (col_index, col) in row.iter().enumerate() {
                 let shader_bundle_index = get_cell_pos_in_grid(row_len, row_index, col_index);
                 // log::debug!(
                 //     "row_len {}, row {}, col {}, cellnum {}",
@@ -328,6 +355,7 @@ impl TiledShadersVisualizer {
                 //     shader_bundle_index,
                 // );
 
+                let tile_color = col.color();
                 let shader_bundle = &mut self.shader_bundles.bundles[shader_bundle_index];
                 gfx.set_buffer_data(
                     &shader_bundle.common_ubo,
@@ -341,15 +369,15 @@ impl TiledShadersVisualizer {
                 gfx.set_buffer_data(
                     &shader_bundle.tile_colors_ubo,
                     &[
-                        col.color.r,
-                        col.color.g,
-                        col.color.b,
+                        tile_color.r,
+                        tile_color.g,
+                        tile_color.b,
                         self.transition.color.r,
                         self.transition.color.g,
                         self.transition.color.b,
                     ],
                 );
-                shader_bundle.draw_filled(gfx, &self.shader_pipeline);
+                shader_bundle.draw_filled(gfx, shader_pipeline);
                 draw.image(&shader_bundle.srt.rt)
                     .position(
                         col_index as f32 * self.layout.tile_size.x,
@@ -368,16 +396,15 @@ impl EmoVisualizer for TiledShadersVisualizer {
 
     fn reset(&mut self, bg_color: Color, text_color: Color, enable_dynamic_text_color: bool) {
         self.model = None;
-        self.transition.color = bg_color;
+        self.model_error = None;
         self.transition.target_color = bg_color;
-        self.transition.mix_factor = 0.0;
-        self.transition.transitioning = false;
-        self.bg_color_for_text = bg_color;
+        self.transition.immediate();
         self.text_color = text_color;
         self.dynamic_text_color = enable_dynamic_text_color;
         self.tiles = vec![];
         self.layout = TilesLayout::none();
         self.refresh_layout = false;
+        self.restart_tiles = false;
     }
 
     fn gracefully_reset(
@@ -392,114 +419,113 @@ impl EmoVisualizer for TiledShadersVisualizer {
         self.tiles = vec![];
         self.layout = TilesLayout::none();
         self.refresh_layout = false;
+        self.restart_tiles = false;
     }
 
     fn update_model(&mut self, analysis: &EmocatTextAnalysis) {
-        let model = TopEmotionsModel::from_analysis(&analysis);
-        let top_emocolors = model.get_top_emocolors(&ColorMapping::PLUTCHIK);
-        self.tiles = top_emocolors
-            .iter()
-            .map(|emocolor| Tile::new(emocolor))
-            .collect();
-        self.transition.target_color = model.get_simple_color();
-        self.bg_color_for_text = self.transition.target_color;
+        let model = match TopEmotionsModel::try_from_source(&analysis, self.analyzer_source) {
+            Ok(model) => model,
+            Err(err) => {
+                log::error!("Paragraph {}: {}", analysis.meta.paragraph, err);
+                // Without a model there is nothing to tile, so fall back to a plain background
+                self.tiles = vec![];
+                self.layout = TilesLayout::none();
+                self.refresh_layout = false;
+                self.transition.target_color = MODEL_ERROR_COLOR;
+                self.model = None;
+                self.model_error = Some(err);
+                return;
+            }
+        };
+        self.tiles = Tile::from_model(&model, &self.color_mapping);
+        self.transition.target_color = model.get_simple_color(&self.color_mapping);
         self.refresh_layout = true;
+        self.restart_tiles = true;
         self.model = Some(model);
+        self.model_error = None;
     }
 
-    fn update_visualization(&mut self) {
-        self.transition.step();
+    fn update_visualization(&mut self, delta: f32) {
+        self.transition.step(delta);
+        self.step_tiles(delta);
         self.update_text_color();
     }
 
+    fn update_layout(&mut self, work_size: Vec2) {
+        if self.tiles.is_empty() {
+            return;
+        }
+        self.prepare_layout(work_size);
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        (self.rng, _) = get_rng(Some(seed));
+    }
+
+    fn snapshot(&self) -> VisualizerSnapshot {
+        let mut snapshot = VisualizerSnapshot::new(
+            self.transition.color,
+            self.transition.target_color,
+            self.text_color,
+        );
+        snapshot.tiles = self
+            .layout
+            .reprs
+            .iter()
+            .map(|row| row.iter().map(|col| hex(col.color())).collect())
+            .collect();
+        snapshot.tile_size = [self.layout.tile_size.x, self.layout.tile_size.y];
+        snapshot
+    }
+
+    fn set_color_mapping(&mut self, color_mapping: ColorMapping) {
+        self.color_mapping = color_mapping;
+    }
+
     fn draw(&mut self, app: &mut App, gfx: &mut Graphics, draw: &mut Draw) {
         // The following call to clear() is important when rendering draw & egui output together.
         draw.clear(self.transition.color);
         // draw.clear(Color::TRANSPARENT);
+        self.update_layout(vec2(draw.width(), draw.height()));
         self.draw_tiles_grid(app, gfx, draw);
+        update_frosted_glass(&mut self.frosted_glass, self.text_backdrop, gfx, draw);
+    }
+
+    fn options(&mut self) -> Vec<VisualizerOption<'_>> {
+        common_options(
+            &mut self.text_backdrop,
+            &mut self.transition,
+            &mut self.contrast_metric,
+            &mut self.text_palette,
+            &mut self.analyzer_source,
+        )
+    }
+
+    fn is_transition_complete(&self) -> bool {
+        self.transition.is_complete()
+    }
+
+    fn get_analyzer_source(&self) -> AnalyzerSource {
+        self.analyzer_source
     }
 
     fn get_text_color(&self) -> Color {
         self.text_color
     }
 
-    fn draw_paragraph(&mut self, draw: &mut Draw, font: &Font, text: &str, work_size: Vec2) {
-        let textbox_width = work_size.x * 0.75;
-        let textbox_height = work_size.y * 0.75;
-        let text_pos_x = work_size.x * 0.5 - textbox_width * 0.5;
-        let text_pos_y = work_size.y * 0.5;
-        let text_color = self.get_text_color();
-
-        let font_size = get_optimal_text_size(
-            draw,
-            font,
-            text,
-            work_size,
-            32.0,
-            textbox_width,
-            textbox_height,
-        );
+    fn get_text_contrast(&self) -> f32 {
+        self.text_contrast
+    }
 
-        if self.text_shadow_style != "None" {
-            let offset = work_size.x * 0.0005;
-            if text_color == Color::WHITE {
-                if self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .alpha(0.25)
-                        .color(Color::BLACK)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x - offset * 3.0, text_pos_y + offset * 3.0)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-                if self.text_shadow_style == "Moderate" || self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .color(Color::BLACK)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x - offset, text_pos_y + offset)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-            } else {
-                if self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .alpha(0.3)
-                        .color(Color::WHITE)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x + offset * 3.0, text_pos_y - offset * 3.0)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-                if self.text_shadow_style == "Moderate" || self.text_shadow_style == "Full" {
-                    draw.text(&font, &text)
-                        .alpha_mode(BlendMode::OVER)
-                        .color(Color::WHITE)
-                        // NOTE: These draw.text fonts size differently than font sizes in egui
-                        .size(scale_font(font_size, work_size))
-                        .max_width(textbox_width)
-                        .position(text_pos_x + offset, text_pos_y - offset)
-                        .v_align_middle()
-                        .h_align_left();
-                }
-            }
-        }
-        draw.text(&font, &text)
-            .alpha_mode(BlendMode::OVER)
-            .color(self.get_text_color())
-            // NOTE: These draw.text fonts size differently than font sizes in egui
-            .size(scale_font(font_size, work_size))
-            .max_width(textbox_width)
-            .position(text_pos_x, text_pos_y)
-            .v_align_middle()
-            .h_align_left();
+    fn get_text_backdrop(&self) -> TextBackdrop {
+        self.text_backdrop
+    }
+
+    fn get_frosted_glass(&self) -> Option<&FrostedGlass> {
+        self.frosted_glass.as_ref()
+    }
+
+    fn get_text_fitter(&mut self) -> &mut TextFitter {
+        &mut self.text_fitter
     }
 }
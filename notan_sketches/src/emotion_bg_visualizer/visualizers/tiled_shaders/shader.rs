@@ -1,6 +1,6 @@
 use crate::shaderutils::ShaderRenderTexture;
 // use notan::draw::*;
-// use notan::log;
+use notan::log;
 use notan::math::Vec2;
 use notan::prelude::*;
 
@@ -41,7 +41,9 @@ impl TileShaderBundle {
     }
 
     pub fn draw_filled(&mut self, gfx: &mut Graphics, pipeline: &Pipeline) {
-        self.srt
-            .draw_filled(gfx, pipeline, vec![&self.common_ubo, &self.tile_colors_ubo]);
+        let uniforms = [&self.common_ubo, &self.tile_colors_ubo];
+        if let Err(err) = self.srt.draw_filled(gfx, pipeline, &uniforms) {
+            log::error!("{}", err);
+        }
     }
 }
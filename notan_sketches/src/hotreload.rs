@@ -0,0 +1,129 @@
+use notan::log;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+
+struct WatchedFile {
+    path: PathBuf,
+    /// None while the file can't be read, such as in the middle of an editor saving it
+    modified: Option<SystemTime>,
+}
+
+impl WatchedFile {
+    fn new(path: PathBuf) -> Self {
+        let modified = modified(&path);
+        Self { path, modified }
+    }
+}
+
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+
+/// Watches files for changes by their modification times. Files that don't exist yet are
+/// watched for when they do.
+pub struct FileWatcher {
+    files: Vec<WatchedFile>,
+}
+
+impl FileWatcher {
+    pub fn new<P: AsRef<Path>>(paths: &[P]) -> Self {
+        Self {
+            files: paths
+                .iter()
+                .map(|path| WatchedFile::new(path.as_ref().to_path_buf()))
+                .collect(),
+        }
+    }
+
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) {
+        self.files
+            .push(WatchedFile::new(path.as_ref().to_path_buf()));
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|file| file.path.as_path())
+    }
+
+    /// True if any of the files were modified since the last poll. Files that went missing
+    /// aren't counted as changed until they're back.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        for file in self.files.iter_mut() {
+            let modified = modified(&file.path);
+            if modified.is_some() && modified != file.modified {
+                file.modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+
+/// An asset loaded from files, such as a shader pipeline, a palette or a brush texture, that
+/// is reloaded when the files change. When reloading fails, such as when a shader doesn't
+/// compile, the error is logged and the last asset that loaded keeps being used.
+pub struct HotAsset<T> {
+    pub asset: T,
+    watcher: FileWatcher,
+}
+
+impl<T> HotAsset<T> {
+    /// Watches `paths` for changes to `asset`, which was loaded from them
+    pub fn new<P: AsRef<Path>>(asset: T, paths: &[P]) -> Self {
+        Self {
+            asset,
+            watcher: FileWatcher::new(paths),
+        }
+    }
+
+    /// Loads the asset for the first time
+    pub fn load<P, F>(paths: &[P], load: F) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+        F: FnOnce() -> Result<T, String>,
+    {
+        Ok(Self::new(load()?, paths))
+    }
+
+    /// Replaces the asset with what `load` loads if any of its files changed. Returns true
+    /// when the asset was replaced.
+    pub fn update<F>(&mut self, load: F) -> bool
+    where
+        F: FnOnce() -> Result<T, String>,
+    {
+        if !self.watcher.poll() {
+            return false;
+        }
+        match load() {
+            Ok(asset) => {
+                self.asset = asset;
+                log::info!("Reloaded {}", self.describe());
+                true
+            }
+            Err(err) => {
+                log::error!("Could not reload {}: {}", self.describe(), err);
+                false
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.watcher
+            .paths()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+
+/// Reads a file that an asset is loaded from, with the path in the error
+pub fn read_asset<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, String> {
+    let path = path.as_ref();
+    fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))
+}
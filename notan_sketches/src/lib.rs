@@ -4,9 +4,13 @@ pub mod emotion_bg_visualizer;
 pub mod enums;
 pub mod fractals;
 pub mod gridutils;
+pub mod hotreload;
 pub mod mathutils;
+pub mod persistence;
+pub mod postfx;
 pub mod schotter;
 pub mod shaderutils;
+pub mod textutils;
 pub mod utils;
 
 // pub fn add(left: usize, right: usize) -> usize {
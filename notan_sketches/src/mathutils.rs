@@ -1,4 +1,6 @@
 use notan::math::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
 
 
 pub fn mid(vec_a: Vec2, vec_b: Vec2) -> Vec2 {
@@ -17,3 +19,54 @@ pub fn get_vec2_midpoint(vec_a: Vec2, vec_b: Vec2) -> Vec2 {
 pub fn get_cell_pos_in_grid(cols_per_row: usize, row: usize, col: usize) -> usize {
     return cols_per_row * row + col;
 }
+
+
+/// Easing curves for animations, mapping linear progress from 0.0 to 1.0 to eased progress
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    /// Starts and ends slowly (sine)
+    #[default]
+    EaseInOut,
+    /// Starts fast and slows down towards the end (cubic ease-out)
+    Cubic,
+    /// Overshoots the end and settles back, like a damped spring
+    Spring,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseInOut,
+        Easing::Cubic,
+        Easing::Spring,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseInOut => "Ease In-Out",
+            Easing::Cubic => "Cubic",
+            Easing::Spring => "Spring",
+        }
+    }
+
+    /// Eased progress for `t`, which is clamped to 0.0..=1.0. Always 0.0 at the start and 1.0
+    /// at the end, but `Spring` goes above 1.0 in between.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => (1.0 - (PI * t).cos()) / 2.0,
+            Easing::Cubic => 1.0 - (1.0 - t).powi(3),
+            // The cosine reaches zero at t = 1.0, so the spring ends exactly on 1.0
+            Easing::Spring => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - (-6.0 * t).exp() * (2.5 * PI * t).cos()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// Prefix for keys in the browser's local storage, and name of the data directory on native
+const APP_NAME: &str = "notan_sketches";
+
+
+/// Loads the text saved under `key` in a previous session, if any.
+///
+/// On native the text is kept in a `<key>.json` file in the user's data directory
+/// (`$XDG_DATA_HOME`, `%APPDATA%` or `~/.local/share`).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_string(key: &str) -> Option<String> {
+    std::fs::read_to_string(data_file(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_string(key: &str, value: &str) -> Result<(), String> {
+    let path = data_file(key).ok_or("no data directory for this user")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, value).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn data_file(key: &str) -> Option<PathBuf> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+        })?;
+    Some(data_dir.join(APP_NAME).join(format!("{}.json", key)))
+}
+
+
+/// Loads the text saved under `key` in the browser's local storage in a previous session,
+/// if any.
+#[cfg(target_arch = "wasm32")]
+pub fn load_string(key: &str) -> Option<String> {
+    local_storage()?.get_item(&storage_key(key)).ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_string(key: &str, value: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("local storage is not available")?
+        .set_item(&storage_key(key), value)
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key(key: &str) -> String {
+    format!("{}.{}", APP_NAME, key)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
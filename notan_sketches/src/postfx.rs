@@ -0,0 +1,334 @@
+use crate::shaderutils::{create_texture_pipeline, ShaderError, ShaderRenderTexture};
+use notan::draw::*;
+use notan::prelude::*;
+
+// NOTE: You will need to `touch` this file to re-compile if the shaders below are changed
+const BLUR_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_blur.frag.glsl");
+const BRIGHT_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_bright.frag.glsl");
+const BLOOM_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_bloom.frag.glsl");
+const VIGNETTE_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_vignette.frag.glsl");
+const GRAIN_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_grain.frag.glsl");
+const CHROMATIC_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_chromatic.frag.glsl");
+const GRADE_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_grade.frag.glsl");
+const DITHER_FRAG: ShaderSource =
+    notan::include_fragment_shader!("examples/assets/shaders/postfx_dither.frag.glsl");
+
+/// Slot of the `PostFx` uniform block shared by the effect shaders, after their textures
+const UNIFORM_SLOT: u32 = 2;
+
+
+/// A full-screen effect of a `PostFxChain`
+#[derive(Debug, Clone)]
+pub enum PostFx {
+    /// Gaussian blur, `radius` pixels wide
+    Blur { radius: f32 },
+    /// Glow around the parts brighter than `threshold` (0 to 1), blurred `radius` pixels
+    /// wide and added back `intensity` times as bright
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        radius: f32,
+    },
+    /// Darkens by up to `strength` (0 to 1) towards the corners, starting `radius` of the way
+    /// out from the center (0 to 1)
+    Vignette { strength: f32, radius: f32 },
+    /// Noise of up to `amount` (0 to 1) that changes every frame
+    FilmGrain { amount: f32 },
+    /// Red and blue split apart towards the edges, by up to `offset` pixels in the corners
+    ChromaticAberration { offset: f32 },
+    /// Colors looked up in `lut`, a texture made by `create_lut_texture()` or an image in the
+    /// same layout, and mixed in by `strength` (0 to 1)
+    ColorGrade {
+        lut: Texture,
+        lut_size: u32,
+        strength: f32,
+    },
+    /// Each channel reduced to `levels` levels with an ordered dither
+    Dither { levels: f32 },
+}
+
+impl PostFx {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PostFx::Blur { .. } => "Blur",
+            PostFx::Bloom { .. } => "Bloom",
+            PostFx::Vignette { .. } => "Vignette",
+            PostFx::FilmGrain { .. } => "Film grain",
+            PostFx::ChromaticAberration { .. } => "Chromatic aberration",
+            PostFx::ColorGrade { .. } => "Color grading",
+            PostFx::Dither { .. } => "Dithering",
+        }
+    }
+}
+
+
+struct PostFxPipelines {
+    blur: Pipeline,
+    bright: Pipeline,
+    bloom: Pipeline,
+    vignette: Pipeline,
+    grain: Pipeline,
+    chromatic: Pipeline,
+    grade: Pipeline,
+    dither: Pipeline,
+}
+
+impl PostFxPipelines {
+    fn new(gfx: &mut Graphics) -> Result<Self, String> {
+        let scene = ["u_texture"];
+        Ok(Self {
+            blur: create_texture_pipeline(gfx, &BLUR_FRAG, &scene)?,
+            bright: create_texture_pipeline(gfx, &BRIGHT_FRAG, &scene)?,
+            bloom: create_texture_pipeline(gfx, &BLOOM_FRAG, &["u_texture", "u_bloom"])?,
+            vignette: create_texture_pipeline(gfx, &VIGNETTE_FRAG, &scene)?,
+            grain: create_texture_pipeline(gfx, &GRAIN_FRAG, &scene)?,
+            chromatic: create_texture_pipeline(gfx, &CHROMATIC_FRAG, &scene)?,
+            grade: create_texture_pipeline(gfx, &GRADE_FRAG, &["u_texture", "u_lut"])?,
+            dither: create_texture_pipeline(gfx, &DITHER_FRAG, &scene)?,
+        })
+    }
+}
+
+
+/// A chain of full-screen effects, applied in order to a texture, such as the render texture
+/// of a `CapturingTexture`, before it's presented or captured. The texture itself is left as
+/// it is, so sketches that paint over their previous frames keep working.
+///
+/// ```ignore
+/// let mut postfx = PostFxChain::new(gfx, work_size.x, work_size.y)?
+///     .with(PostFx::Bloom { threshold: 0.7, intensity: 0.8, radius: 24.0 })
+///     .with(PostFx::Vignette { strength: 0.5, radius: 0.4 });
+/// let processed = postfx.apply(gfx, &capture.render_texture, app.timer.elapsed_f32())?;
+/// draw.image(processed);
+/// ```
+pub struct PostFxChain {
+    pub effects: Vec<PostFx>,
+    pipelines: PostFxPipelines,
+    ubo: Buffer,
+    /// Effects draw to one frame while sampling the other
+    frames: [ShaderRenderTexture; 2],
+    /// Bright parts of the scene for bloom, blurred from one to the other
+    bloom: [ShaderRenderTexture; 2],
+    /// Index of the frame drawn last
+    last: usize,
+}
+
+impl PostFxChain {
+    pub fn new(gfx: &mut Graphics, width: f32, height: f32) -> Result<Self, String> {
+        Ok(Self {
+            effects: vec![],
+            pipelines: PostFxPipelines::new(gfx)?,
+            ubo: gfx
+                .create_uniform_buffer(UNIFORM_SLOT, "PostFx")
+                .with_data(&uniform_data([0.0; 4], width, height, 0.0))
+                .build()?,
+            frames: create_frames(gfx, width, height),
+            bloom: create_frames(gfx, width, height),
+            last: 0,
+        })
+    }
+
+    pub fn with(mut self, effect: PostFx) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// The result of the last `apply()`
+    pub fn output(&self) -> &RenderTexture {
+        &self.frames[self.last].rt
+    }
+
+    /// Applies the effects to `source`, at `time` seconds for the effects that change over
+    /// time. With no effects, the output is a copy of `source`. The frames are resized to
+    /// match `source` if needed.
+    pub fn apply(
+        &mut self,
+        gfx: &mut Graphics,
+        source: &Texture,
+        time: f32,
+    ) -> Result<&RenderTexture, ShaderError> {
+        let (width, height) = source.size();
+        if self.frames[0].rt.size() != (width.floor(), height.floor()) {
+            self.frames = create_frames(gfx, width, height);
+            self.bloom = create_frames(gfx, width, height);
+        }
+
+        // Index of the frame drawn last, or None while the next effect reads from `source`
+        let mut last = None;
+        for effect in self.effects.iter() {
+            let p = &self.pipelines;
+            let (input, target) = next_frame(&mut self.frames, last, source);
+            let mut pass = Pass {
+                gfx,
+                ubo: &self.ubo,
+                time,
+            };
+            match effect {
+                PostFx::Blur { radius } => {
+                    // Horizontally into the next frame, then vertically into the one after
+                    pass.run(target, &p.blur, [1.0, 0.0, *radius, 0.0], &[input])?;
+                    last = Some(next_index(last));
+                    let (input, target) = next_frame(&mut self.frames, last, source);
+                    pass.run(target, &p.blur, [0.0, 1.0, *radius, 0.0], &[input])?;
+                }
+                PostFx::Bloom {
+                    threshold,
+                    intensity,
+                    radius,
+                } => {
+                    let [glow, half_blurred] = &mut self.bloom;
+                    pass.run(glow, &p.bright, [*threshold, 0.0, 0.0, 0.0], &[input])?;
+                    let params = [1.0, 0.0, *radius, 0.0];
+                    pass.run(half_blurred, &p.blur, params, &[&glow.rt])?;
+                    let params = [0.0, 1.0, *radius, 0.0];
+                    pass.run(glow, &p.blur, params, &[&half_blurred.rt])?;
+                    let params = [*intensity, 0.0, 0.0, 0.0];
+                    pass.run(target, &p.bloom, params, &[input, &glow.rt])?;
+                }
+                PostFx::Vignette { strength, radius } => {
+                    let params = [*strength, *radius, 0.0, 0.0];
+                    pass.run(target, &p.vignette, params, &[input])?;
+                }
+                PostFx::FilmGrain { amount } => {
+                    pass.run(target, &p.grain, [*amount, 0.0, 0.0, 0.0], &[input])?;
+                }
+                PostFx::ChromaticAberration { offset } => {
+                    pass.run(target, &p.chromatic, [*offset, 0.0, 0.0, 0.0], &[input])?;
+                }
+                PostFx::ColorGrade {
+                    lut,
+                    lut_size,
+                    strength,
+                } => {
+                    let params = [*lut_size as f32, *strength, 0.0, 0.0];
+                    pass.run(target, &p.grade, params, &[input, lut])?;
+                }
+                PostFx::Dither { levels } => {
+                    pass.run(target, &p.dither, [*levels, 0.0, 0.0, 0.0], &[input])?;
+                }
+            }
+            last = Some(next_index(last));
+        }
+
+        match last {
+            Some(index) => self.last = index,
+            None => {
+                let rt = &self.frames[0].rt;
+                let mut draw = rt.create_draw();
+                draw.clear(Color::TRANSPARENT);
+                draw.image(source).size(width, height);
+                gfx.render_to(rt, &draw);
+                self.last = 0;
+            }
+        }
+        Ok(self.output())
+    }
+}
+
+
+/// What every effect pass is drawn with
+struct Pass<'a> {
+    gfx: &'a mut Graphics,
+    ubo: &'a Buffer,
+    time: f32,
+}
+
+impl Pass<'_> {
+    fn run(
+        &mut self,
+        target: &mut ShaderRenderTexture,
+        pipeline: &Pipeline,
+        params: [f32; 4],
+        textures: &[&Texture],
+    ) -> Result<(), ShaderError> {
+        let (width, height) = target.rt.size();
+        self.gfx
+            .set_buffer_data(self.ubo, &uniform_data(params, width, height, self.time));
+        target.draw_textured(self.gfx, pipeline, &[self.ubo], textures)
+    }
+}
+
+
+fn create_frames(gfx: &mut Graphics, width: f32, height: f32) -> [ShaderRenderTexture; 2] {
+    [
+        ShaderRenderTexture::new(gfx, width, height),
+        ShaderRenderTexture::new(gfx, width, height),
+    ]
+}
+
+
+fn next_index(last: Option<usize>) -> usize {
+    match last {
+        Some(0) => 1,
+        _ => 0,
+    }
+}
+
+
+/// The texture the next effect reads, which is `source` for the first effect, and the frame
+/// it draws to
+fn next_frame<'a>(
+    frames: &'a mut [ShaderRenderTexture; 2],
+    last: Option<usize>,
+    source: &'a Texture,
+) -> (&'a Texture, &'a mut ShaderRenderTexture) {
+    let [first, second] = frames;
+    match last {
+        None => (source, first),
+        Some(0) => (&first.rt, second),
+        Some(_) => (&second.rt, first),
+    }
+}
+
+
+/// Contents of the `PostFx` uniform block, padded to the 16 byte alignment of std140
+fn uniform_data(params: [f32; 4], width: f32, height: f32, time: f32) -> [f32; 8] {
+    [
+        params[0], params[1], params[2], params[3], width, height, time, 0.0,
+    ]
+}
+
+
+/// RGBA pixels of a color grading LUT for `PostFx::ColorGrade`, made by grading every color
+/// of a `size` x `size` x `size` cube. The LUT is a strip of `size` slices of `size` x `size`
+/// pixels, with red increasing across each slice, green down it, and blue from slice to slice.
+/// An identity `grade` gives a LUT that leaves colors as they are.
+pub fn lut_pixels<F>(size: u32, grade: F) -> Vec<u8>
+where
+    F: Fn([f32; 3]) -> [f32; 3],
+{
+    let max = (size.max(2) - 1) as f32;
+    let mut pixels = Vec::with_capacity((size * size * size * 4) as usize);
+    for green in 0..size {
+        for blue in 0..size {
+            for red in 0..size {
+                let graded = grade([red as f32 / max, green as f32 / max, blue as f32 / max]);
+                for channel in graded {
+                    pixels.push((channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+                pixels.push(255);
+            }
+        }
+    }
+    pixels
+}
+
+
+/// A color grading LUT for `PostFx::ColorGrade`, see `lut_pixels()`. 16 to 32 is plenty for
+/// `size`, as colors are blended between the LUT's.
+pub fn create_lut_texture<F>(gfx: &mut Graphics, size: u32, grade: F) -> Result<Texture, String>
+where
+    F: Fn([f32; 3]) -> [f32; 3],
+{
+    gfx.create_texture()
+        .from_bytes(&lut_pixels(size, grade), size * size, size)
+        .with_filter(TextureFilter::Linear, TextureFilter::Linear)
+        .build()
+}
@@ -1,10 +1,69 @@
+#[cfg(debug_assertions)]
+use crate::hotreload::read_asset;
+use crate::hotreload::FileWatcher;
 use notan::draw::*;
 // use notan::log;
 use notan::math::Vec2;
 use notan::prelude::*;
+use std::fmt;
+
+/// Uniform buffers a shader can be given. WebGL 2 and OpenGL ES 3.0 guarantee at least 12
+/// uniform blocks per shader stage.
+pub const MAX_UNIFORM_BUFFERS: usize = 12;
+/// Textures a shader can sample. WebGL 2 and OpenGL ES 3.0 guarantee at least 16 texture
+/// units for fragment shaders.
+pub const MAX_TEXTURES: usize = 16;
+
+// NOTE: You will need to `touch` this file to re-compile if the shader below is changed
+const FULLSCREEN_VERT: ShaderSource =
+    notan::include_vertex_shader!("examples/assets/shaders/fullscreen.vert.glsl");
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShaderError {
+    TooManyUniforms {
+        count: usize,
+        max: usize,
+    },
+    TooManyTextures {
+        count: usize,
+        max: usize,
+    },
+    /// A texture can't be sampled while it's being drawn to. Feedback effects need two
+    /// render textures, drawing to one while sampling the other.
+    SamplesTarget,
+    /// The buffers a shader pass draws with could not be created
+    Buffers(String),
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderError::TooManyUniforms { count, max } => write!(
+                f,
+                "{} uniform buffers were given, but shaders can only be given {}",
+                count, max
+            ),
+            ShaderError::TooManyTextures { count, max } => write!(
+                f,
+                "{} textures were given, but shaders can only sample {}",
+                count, max
+            ),
+            ShaderError::SamplesTarget => {
+                write!(f, "a shader can't sample the texture it's drawing to")
+            }
+            ShaderError::Buffers(err) => write!(f, "could not create shader buffers: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
 
 pub struct ShaderRenderTexture {
     pub rt: RenderTexture,
+    /// For `draw_textured()`, created the first time it's called
+    quad: Option<FullscreenQuad>,
 }
 
 impl ShaderRenderTexture {
@@ -14,96 +73,269 @@ impl ShaderRenderTexture {
             .build()
             .unwrap();
         // log::info!("ShaderRenderTexture created: {width} x {height}");
-        Self { rt }
+        Self { rt, quad: None }
     }
 
+    /// Draws with `draw_fn` to the texture, with shapes drawn by `pipeline`, which is given
+    /// the `uniforms` in order. Simple shaders may take no uniforms at all.
     pub fn draw<F>(
         &mut self,
         gfx: &mut Graphics,
         pipeline: &Pipeline,
-        // Up to 5 uniforms are allowed for now (see hack below)
-        uniforms: Vec<&Buffer>,
+        uniforms: &[&Buffer],
         draw_fn: F,
-    ) where
+    ) -> Result<(), ShaderError>
+    where
         F: Fn(&mut Draw),
     {
+        check_uniform_count(uniforms.len())?;
+
         let rt_draw = &mut self.rt.create_draw();
         // Texture should be cleared so that shader alpha changes register.
         // @TODO: This could be made optional to support situations where draw retention is
         // actually desired.
         rt_draw.clear(Color::TRANSPARENT);
 
-        // HACKY WAY OF BUILDING CUSTOM PIPELINE for n uniforms. Revisit later.
-        match uniforms.len() {
-            5 => {
-                rt_draw
-                    .shape_pipeline()
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[0])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[1])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[2])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[3])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[4]);
-            }
-            4 => {
-                rt_draw
-                    .shape_pipeline()
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[0])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[1])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[2])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[3]);
-            }
-            3 => {
-                rt_draw
-                    .shape_pipeline()
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[0])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[1])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[2]);
-            }
-            2 => {
-                rt_draw
-                    .shape_pipeline()
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[0])
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[1]);
-            }
-            1 => {
-                rt_draw
-                    .shape_pipeline()
-                    .pipeline(&pipeline)
-                    .uniform_buffer(&uniforms[0]);
-            }
-            _ => panic!(concat!(
-                "Max number of uniforms is 5 due to Irfan's hacky implementation! ",
-                "You can increase this by updating notan_sketches::shaders::ShaderRenderTexture::draw()"
-            )),
-        }
+        // The custom pipeline is set when the builder is dropped at the end of the statement
+        uniforms.iter().fold(
+            rt_draw.shape_pipeline().pipeline(pipeline),
+            |builder, uniform| builder.uniform_buffer(uniform),
+        );
+
         draw_fn(rt_draw);
         rt_draw.shape_pipeline().remove();
         gfx.render_to(&self.rt, rt_draw);
+        Ok(())
     }
 
     /// Common draw that sizes the shader to the whole texture
-    pub fn draw_filled(&mut self, gfx: &mut Graphics, pipeline: &Pipeline, uniforms: Vec<&Buffer>) {
-        self.draw(gfx, &pipeline, uniforms, |srtdraw| {
+    pub fn draw_filled(
+        &mut self,
+        gfx: &mut Graphics,
+        pipeline: &Pipeline,
+        uniforms: &[&Buffer],
+    ) -> Result<(), ShaderError> {
+        self.draw(gfx, pipeline, uniforms, |srtdraw| {
             srtdraw
                 .rect((0.0, 0.0), (srtdraw.width(), srtdraw.height()))
                 .fill_color(Color::GRAY)
                 .fill();
+        })
+    }
+
+    /// Fills the texture with `pipeline`, a pipeline made by `create_texture_pipeline()`,
+    /// which is given the `uniforms` and samples the `textures`. Textures are bound in the
+    /// order of the pipeline's texture names, and are sampled with the filtering and wrapping
+    /// they were created with.
+    pub fn draw_textured(
+        &mut self,
+        gfx: &mut Graphics,
+        pipeline: &Pipeline,
+        uniforms: &[&Buffer],
+        textures: &[&Texture],
+    ) -> Result<(), ShaderError> {
+        check_uniform_count(uniforms.len())?;
+        check_texture_count(textures.len())?;
+        if textures.iter().any(|texture| texture.id() == self.rt.id()) {
+            return Err(ShaderError::SamplesTarget);
+        }
+        let quad = match &self.quad {
+            Some(quad) => quad,
+            None => self
+                .quad
+                .insert(FullscreenQuad::new(gfx).map_err(ShaderError::Buffers)?),
+        };
+        let mut renderer = gfx.create_renderer();
+        renderer.begin(Some(ClearOptions::color(Color::TRANSPARENT)));
+        renderer.set_pipeline(pipeline);
+        for (location, texture) in textures.iter().enumerate() {
+            renderer.bind_texture(location as u32, texture);
+        }
+        renderer.bind_buffers(&[&quad.vbo, &quad.ibo]);
+        for uniform in uniforms {
+            renderer.bind_buffer(uniform);
+        }
+        renderer.draw(0, 6);
+        renderer.end();
+        gfx.render_to(&self.rt, &renderer);
+        Ok(())
+    }
+}
+
+
+/// Name the previous frame of a `FeedbackRenderTexture` is sampled by, which must be the
+/// first texture name of its pipeline
+pub const PREV_FRAME_TEXTURE: &str = "u_prev";
+
+
+/// Double-buffered render textures for feedback effects such as trails, reaction-diffusion and
+/// cellular automata, where each frame is drawn from the one before it. Each draw goes to one
+/// texture while sampling the other as `u_prev`, and then they swap.
+pub struct FeedbackRenderTexture {
+    frames: [ShaderRenderTexture; 2],
+    /// Index of the frame drawn last
+    current: usize,
+}
+
+impl FeedbackRenderTexture {
+    pub fn new(gfx: &mut Graphics, width: f32, height: f32) -> Self {
+        Self {
+            frames: [
+                ShaderRenderTexture::new(gfx, width, height),
+                ShaderRenderTexture::new(gfx, width, height),
+            ],
+            current: 0,
+        }
+    }
+
+    /// The frame drawn last
+    pub fn texture(&self) -> &RenderTexture {
+        &self.frames[self.current].rt
+    }
+
+    /// Replaces the frame drawn last with what `draw_fn` draws, such as the starting state of
+    /// a simulation
+    pub fn seed<F>(&mut self, gfx: &mut Graphics, draw_fn: F)
+    where
+        F: Fn(&mut Draw),
+    {
+        let rt = &self.frames[self.current].rt;
+        let rt_draw = &mut rt.create_draw();
+        rt_draw.clear(Color::TRANSPARENT);
+        draw_fn(rt_draw);
+        gfx.render_to(rt, rt_draw);
+    }
+
+    /// Draws the next frame with `pipeline`, a pipeline made by `create_texture_pipeline()`
+    /// whose first texture name is `PREV_FRAME_TEXTURE`. The frame drawn last is bound to it,
+    /// followed by the `textures`.
+    pub fn draw(
+        &mut self,
+        gfx: &mut Graphics,
+        pipeline: &Pipeline,
+        uniforms: &[&Buffer],
+        textures: &[&Texture],
+    ) -> Result<(), ShaderError> {
+        let [first, second] = &mut self.frames;
+        let (prev, next) = if self.current == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let prev_frame: &Texture = &prev.rt;
+        let mut frame_textures = vec![prev_frame];
+        frame_textures.extend_from_slice(textures);
+        next.draw_textured(gfx, pipeline, uniforms, &frame_textures)?;
+        self.current = 1 - self.current;
+        Ok(())
+    }
+}
+
+
+fn check_uniform_count(count: usize) -> Result<(), ShaderError> {
+    if count > MAX_UNIFORM_BUFFERS {
+        return Err(ShaderError::TooManyUniforms {
+            count,
+            max: MAX_UNIFORM_BUFFERS,
+        });
+    }
+    Ok(())
+}
+
+
+fn check_texture_count(count: usize) -> Result<(), ShaderError> {
+    if count > MAX_TEXTURES {
+        return Err(ShaderError::TooManyTextures {
+            count,
+            max: MAX_TEXTURES,
         });
     }
+    Ok(())
+}
+
+
+/// Two triangles covering the whole render texture, for shaders that only sample textures
+struct FullscreenQuad {
+    vbo: Buffer,
+    ibo: Buffer,
+}
+
+impl FullscreenQuad {
+    fn new(gfx: &mut Graphics) -> Result<Self, String> {
+        #[rustfmt::skip]
+        let positions: [f32; 8] = [
+            -1.0, -1.0,
+            1.0, -1.0,
+            1.0, 1.0,
+            -1.0, 1.0,
+        ];
+        Ok(Self {
+            vbo: gfx
+                .create_vertex_buffer()
+                .with_info(&fullscreen_vertex_info())
+                .with_data(&positions)
+                .build()?,
+            ibo: gfx
+                .create_index_buffer()
+                .with_data(&[0, 1, 2, 0, 2, 3])
+                .build()?,
+        })
+    }
+}
+
+
+fn fullscreen_vertex_info() -> VertexInfo {
+    VertexInfo::new().attr(0, VertexFormat::Float32x2)
+}
+
+
+/// Pipeline for `ShaderRenderTexture::draw_textured()`. The fragment shader gets the uvs of
+/// the render texture as `layout(location = 0) in vec2 v_uvs`, and samples the textures
+/// named in `textures`, in the order they will be given to `draw_textured()`.
+///
+/// Render textures are sampled the right way up, but images are stored top row first, so
+/// shaders need to sample them at `vec2(v_uvs.x, 1.0 - v_uvs.y)`.
+pub fn create_texture_pipeline(
+    device: &mut Device,
+    fragment: &ShaderSource,
+    textures: &[&str],
+) -> Result<Pipeline, String> {
+    let mut builder = device
+        .create_pipeline()
+        .from(&FULLSCREEN_VERT, fragment)
+        .with_vertex_info(&fullscreen_vertex_info());
+    for (location, name) in textures.iter().enumerate() {
+        builder = builder.with_texture_location(location as u32, name);
+    }
+    builder.build()
+}
+
+
+/// Vertex shader of `create_hot_texture_pipeline()` pipelines
+pub const FULLSCREEN_VERT_PATH: &str = "examples/assets/shaders/fullscreen.vert.glsl";
+/// Vertex shader of notan's shape pipeline, for `create_hot_shape_pipeline()`
+pub const SHAPES_VERT_PATH: &str = "examples/assets/shaders/shapes.vert.glsl";
+
+
+// Hot-reloading texture pipeline for debug mode
+#[cfg(debug_assertions)]
+pub fn create_hot_texture_pipeline(
+    device: &mut Device,
+    vertex_path: &str,
+    fragment_path: &str,
+    textures: &[&str],
+) -> Result<Pipeline, String> {
+    let vert = read_asset(vertex_path)?;
+    let frag = read_asset(fragment_path)?;
+
+    let mut builder = device
+        .create_pipeline()
+        .from_raw(&vert, &frag)
+        .with_vertex_info(&fullscreen_vertex_info());
+    for (location, name) in textures.iter().enumerate() {
+        builder = builder.with_texture_location(location as u32, name);
+    }
+    builder.build()
 }
 
 // Taken from notan_draw::shapes::painter
@@ -114,14 +346,15 @@ fn vertex_info() -> VertexInfo {
         .attr(1, VertexFormat::Float32x4)
 }
 
-// Hot-reloading shape pipeline for debug mode
+// Hot-reloading shape pipeline for debug mode. `vertex_path` is usually `SHAPES_VERT_PATH`.
 #[cfg(debug_assertions)]
 pub fn create_hot_shape_pipeline(
     device: &mut Device,
+    vertex_path: &str,
     fragment_path: &str,
 ) -> Result<Pipeline, String> {
-    let vert = std::fs::read("examples/assets/shaders/shapes.vert.glsl").unwrap();
-    let frag = std::fs::read(fragment_path).unwrap();
+    let vert = read_asset(vertex_path)?;
+    let frag = read_asset(fragment_path)?;
 
     device
         .create_pipeline()
@@ -136,21 +369,24 @@ pub fn create_hot_shape_pipeline(
 #[cfg(not(debug_assertions))]
 pub use notan::draw::create_shape_pipeline;
 
+/// Tells when shaders need to be reloaded, which is when any of their files were modified.
+/// Keep using the last pipeline when reloading fails, so that a shader with a compile error
+/// doesn't stop the sketch. See `crate::hotreload::HotAsset` for assets that are reloaded
+/// without a manager.
 pub struct ShaderReloadManager {
     needs_reload: bool,
-    pub frame: usize,
+    watcher: FileWatcher,
 }
 
-impl Default for ShaderReloadManager {
-    fn default() -> Self {
+impl ShaderReloadManager {
+    /// Watches the shader files at `paths`, such as a vertex and a fragment shader
+    pub fn new(paths: &[&str]) -> Self {
         Self {
             needs_reload: false,
-            frame: 0,
+            watcher: FileWatcher::new(paths),
         }
     }
-}
 
-impl ShaderReloadManager {
     pub fn needs_reload(&mut self) -> bool {
         if self.needs_reload {
             self.needs_reload = false;
@@ -160,10 +396,9 @@ impl ShaderReloadManager {
     }
 
     pub fn update(&mut self) {
-        if self.frame % 60 == 0 {
+        if self.watcher.poll() {
             self.needs_reload = true;
         }
-        self.frame += 1;
     }
 }
 
@@ -0,0 +1,256 @@
+use notan::draw::*;
+use notan::math::{vec2, Vec2};
+use notan::prelude::*;
+use std::collections::HashMap;
+
+/// Font sizes closer than this are considered the same when fitting text
+const SIZE_PRECISION: f32 = 0.5;
+/// Fits, and measurements, `TextFitter` keeps before starting over, enough for every
+/// paragraph of a long work
+const MAX_CACHED_FITS: usize = 256;
+/// Allowance for rounding in measured text bounds
+const BOUNDS_TOLERANCE: f32 = 0.01;
+
+
+/// The box, and range of font sizes, to fit text in. All in draw units, i.e. font sizes are
+/// already scaled to the work size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitBounds {
+    pub max_width: f32,
+    pub max_height: f32,
+    pub min_size: f32,
+    pub max_size: f32,
+}
+
+
+/// Text laid out to fit in `FitBounds`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFit {
+    /// The largest size the text fits at, or the minimum size if it doesn't fit at all
+    pub size: f32,
+    /// The text split into pages that each fit at `size`. Only one page when the whole text
+    /// fits.
+    pub pages: Vec<TextPage>,
+}
+
+impl TextFit {
+    pub fn fits(&self) -> bool {
+        self.pages.len() <= 1
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPage {
+    pub text: String,
+    /// Index of the line of the whole text that the page starts on
+    pub first_line: usize,
+}
+
+impl TextPage {
+    /// Indices of the lines of the whole text that are on the page, including lines that
+    /// only partly are
+    pub fn lines(&self) -> std::ops::Range<usize> {
+        self.first_line..self.first_line + self.text.split('\n').count()
+    }
+}
+
+
+/// Fits text to bounds by measuring it with a `Draw`, caching the results so that text is
+/// only measured again when the text, font or bounds change.
+#[derive(Default)]
+pub struct TextFitter {
+    cache: HashMap<FitKey, TextFit>,
+    measured: HashMap<MeasureKey, Vec2>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct FitKey {
+    text: String,
+    font_id: u64,
+    /// Bits of the `FitBounds` values, which depend on the work size
+    bounds: [u32; 4],
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct MeasureKey {
+    text: String,
+    font_id: u64,
+    /// Bits of the size and wrapping width
+    size: [u32; 2],
+}
+
+impl TextFitter {
+    /// Note that measuring draws the text transparently on `draw`, once per size tried, when
+    /// the fit isn't cached yet
+    pub fn fit(&mut self, draw: &mut Draw, font: &Font, text: &str, bounds: FitBounds) -> &TextFit {
+        let key = FitKey {
+            text: text.to_string(),
+            font_id: font.id(),
+            bounds: [
+                bounds.max_width.to_bits(),
+                bounds.max_height.to_bits(),
+                bounds.min_size.to_bits(),
+                bounds.max_size.to_bits(),
+            ],
+        };
+        if self.cache.len() >= MAX_CACHED_FITS && !self.cache.contains_key(&key) {
+            self.cache.clear();
+        }
+        self.cache.entry(key).or_insert_with(|| {
+            fit_text(text, bounds, |text, size| {
+                measure_text(draw, font, text, size, bounds.max_width)
+            })
+        })
+    }
+
+    /// Width and height of the text drawn at `size`, wrapped at `max_width`. Like `fit()`,
+    /// the text is only drawn transparently on `draw` to measure it when the measurement
+    /// isn't cached yet.
+    pub fn measure(
+        &mut self,
+        draw: &mut Draw,
+        font: &Font,
+        text: &str,
+        size: f32,
+        max_width: f32,
+    ) -> Vec2 {
+        let key = MeasureKey {
+            text: text.to_string(),
+            font_id: font.id(),
+            size: [size.to_bits(), max_width.to_bits()],
+        };
+        if self.measured.len() >= MAX_CACHED_FITS && !self.measured.contains_key(&key) {
+            self.measured.clear();
+        }
+        *self
+            .measured
+            .entry(key)
+            .or_insert_with(|| measure_text(draw, font, text, size, max_width))
+    }
+}
+
+
+/// Width and height of the text drawn at `size`, wrapped at `max_width`. The text is drawn
+/// transparently on `draw` to measure it, so prefer the cached `TextFitter::measure()` for
+/// anything measured every frame.
+pub fn measure_text(draw: &mut Draw, font: &Font, text: &str, size: f32, max_width: f32) -> Vec2 {
+    draw.text(font, text)
+        .alpha_mode(BlendMode::OVER)
+        .color(Color::TRANSPARENT)
+        .size(size)
+        .max_width(max_width)
+        .h_align_left()
+        .v_align_top();
+    let bounds = draw.last_text_bounds();
+    vec2(bounds.width, bounds.height)
+}
+
+
+/// Finds the largest size the text fits at, paginating it at the minimum size when it
+/// doesn't fit at all. `measure` returns the width and height of text at a given size.
+pub fn fit_text(
+    text: &str,
+    bounds: FitBounds,
+    mut measure: impl FnMut(&str, f32) -> Vec2,
+) -> TextFit {
+    let mut fits = |text: &str, size: f32| {
+        let measured = measure(text, size);
+        measured.x <= bounds.max_width + BOUNDS_TOLERANCE
+            && measured.y <= bounds.max_height + BOUNDS_TOLERANCE
+    };
+    let size = fit_size(bounds.min_size, bounds.max_size, |size| fits(text, size));
+    match size {
+        Some(size) => TextFit {
+            size,
+            pages: vec![TextPage {
+                text: text.to_string(),
+                first_line: 0,
+            }],
+        },
+        None => TextFit {
+            size: bounds.min_size,
+            pages: paginate(text, |page| fits(page, bounds.min_size)),
+        },
+    }
+}
+
+
+/// Binary searches for the largest size in `min_size..=max_size`, to within
+/// `SIZE_PRECISION`, for which `fits` is true. `None` if it's false even at `min_size`.
+/// Assumes that anything that fits at a size also fits at smaller sizes.
+pub fn fit_size(min_size: f32, max_size: f32, mut fits: impl FnMut(f32) -> bool) -> Option<f32> {
+    if fits(max_size) {
+        return Some(max_size);
+    }
+    if min_size >= max_size || !fits(min_size) {
+        return None;
+    }
+    // `low` always fits and `high` never does
+    let (mut low, mut high) = (min_size, max_size);
+    while high - low > SIZE_PRECISION {
+        let mid = (low + high) * 0.5;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(low)
+}
+
+
+/// Splits the text between words into pages for which `fits` is true, keeping as many words
+/// on each page as possible. Line breaks within a page are kept. A word that doesn't fit on
+/// its own gets a page to itself.
+pub fn paginate(text: &str, mut fits: impl FnMut(&str) -> bool) -> Vec<TextPage> {
+    let ends = word_ends(text);
+    let mut pages = vec![];
+    let mut start = 0;
+    let mut first = 0;
+    while first < ends.len() {
+        // Binary search for the last word of the page
+        let mut last = first;
+        let (mut low, mut high) = (first + 1, ends.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if fits(text[start..ends[mid]].trim()) {
+                last = mid;
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let page = text[start..ends[last]].trim();
+        // Line breaks skipped by trimming the start of the page count too
+        let first_line = text[..ends[last] - page.len()].matches('\n').count();
+        pages.push(TextPage {
+            text: page.to_string(),
+            first_line,
+        });
+        start = ends[last];
+        first = last + 1;
+    }
+    pages
+}
+
+
+/// Byte index just past each word of the text
+fn word_ends(text: &str) -> Vec<usize> {
+    let mut ends = vec![];
+    let mut in_word = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if in_word {
+                ends.push(i);
+            }
+            in_word = false;
+        } else {
+            in_word = true;
+        }
+    }
+    if in_word {
+        ends.push(text.len());
+    }
+    ends
+}
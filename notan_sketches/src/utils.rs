@@ -584,15 +584,27 @@ impl CapturingTexture {
     /// Capture render texture to file.
     /// On native: If supersampled, saves then downsamples automatically.
     /// On WASM: Saves the full supersampled image.
-    #[cfg(not(target_arch = "wasm32"))]
     pub fn capture(&mut self, app: &mut App, gfx: &mut Graphics) {
+        self.save(app, gfx, &self.render_texture);
+        self.capture_lock = true;
+    }
+
+    /// Capture `texture` in place of the render texture, such as the render texture after
+    /// post-processing by a `PostFxChain`. It should be the size of the render texture.
+    pub fn capture_texture(&mut self, app: &mut App, gfx: &mut Graphics, texture: &RenderTexture) {
+        self.save(app, gfx, texture);
+        self.capture_lock = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&self, app: &mut App, gfx: &mut Graphics, texture: &RenderTexture) {
         log::info!("Beginning capture at {}", app.timer.elapsed_f32());
         let filepath = format!("{}_{}.png", self.capture_to, app.timer.elapsed_f32());
 
         if self.supersample_factor > 1.0 {
             // Save supersampled version temporarily
             let temp_filepath = format!("{}_temp.png", self.capture_to);
-            self.render_texture.to_file(gfx, &temp_filepath).unwrap();
+            texture.to_file(gfx, &temp_filepath).unwrap();
 
             // Load, downsample, and save final version
             log::info!("Downsampling the supersampled capture...");
@@ -620,16 +632,14 @@ impl CapturingTexture {
                 filepath
             );
         } else {
-            self.render_texture.to_file(gfx, &filepath).unwrap();
+            texture.to_file(gfx, &filepath).unwrap();
             log::info!("Saved capture: {}", filepath);
         }
-
-        self.capture_lock = true;
     }
 
     /// WASM version: saves full image (no downsampling)
     #[cfg(target_arch = "wasm32")]
-    pub fn capture(&mut self, app: &mut App, gfx: &mut Graphics) {
+    fn save(&self, app: &mut App, gfx: &mut Graphics, texture: &RenderTexture) {
         log::debug!("Beginning capture at {}", app.timer.elapsed_f32());
         let filepath = format!("{}_{}.png", self.capture_to, app.timer.elapsed_f32());
 
@@ -640,8 +650,7 @@ impl CapturingTexture {
             );
         }
 
-        self.render_texture.to_file(gfx, &filepath).unwrap();
-        self.capture_lock = true;
+        texture.to_file(gfx, &filepath).unwrap();
     }
 
     pub fn periodic_capture(&mut self, app: &mut App, gfx: &mut Graphics) {
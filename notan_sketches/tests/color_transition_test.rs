@@ -0,0 +1,273 @@
+use notan::prelude::Color;
+use notan_sketches::emotion_bg_visualizer::visualizers::color_transition::{
+    ColorTimeline, ColorTransition, InterpolationSpace, Keyframe, Repeat,
+};
+use notan_sketches::mathutils::Easing;
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+
+fn transition(duration: f32, easing: Easing) -> ColorTransition {
+    let mut transition = ColorTransition::new(Color::WHITE);
+    transition.duration = duration;
+    transition.easing = easing;
+    transition
+}
+
+// ===== Easing =====
+
+#[test]
+fn test_easings_start_at_zero_and_end_at_one() {
+    for easing in Easing::ALL {
+        assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+    }
+}
+
+#[test]
+fn test_easing_clamps_progress() {
+    assert_eq!(Easing::Linear.apply(-0.5), 0.0);
+    assert_eq!(Easing::Linear.apply(1.5), 1.0);
+}
+
+#[test]
+fn test_spring_overshoots() {
+    let max = (0..=100)
+        .map(|i| Easing::Spring.apply(i as f32 / 100.0))
+        .fold(0.0, f32::max);
+    assert!(max > 1.0);
+}
+
+// ===== Transitions =====
+
+#[test]
+fn test_progress_follows_elapsed_time() {
+    let mut transition = transition(2.0, Easing::Linear);
+    assert_eq!(transition.progress(), 1.0);
+    transition.set_target(RED);
+    assert_eq!(transition.progress(), 0.0);
+    transition.step(0.5);
+    assert_eq!(transition.progress(), 0.25);
+    transition.step(0.5);
+    assert_eq!(transition.progress(), 0.5);
+}
+
+#[test]
+fn test_step_signals_completion_once() {
+    let mut transition = transition(1.0, Easing::EaseInOut);
+    transition.set_target(RED);
+    let completions: Vec<bool> = (0..10).map(|_| transition.step(0.25)).collect();
+    assert_eq!(completions.iter().filter(|done| **done).count(), 1);
+    assert!(completions[3]);
+    assert_eq!(transition.color, RED);
+    assert!(!transition.transitioning);
+    assert!(transition.is_complete());
+}
+
+#[test]
+fn test_new_target_is_not_complete_before_stepping() {
+    let mut transition = transition(1.0, Easing::Linear);
+    assert!(transition.is_complete());
+    // As the visualizers set it, without set_target()
+    transition.target_color = RED;
+    assert!(!transition.is_complete());
+    transition.step(0.5);
+    assert!(!transition.is_complete());
+    transition.step(0.5);
+    assert!(transition.is_complete());
+}
+
+#[test]
+fn test_transition_is_independent_of_frame_rate() {
+    let mut at_30_fps = transition(1.0, Easing::Cubic);
+    let mut at_120_fps = transition(1.0, Easing::Cubic);
+    at_30_fps.set_target(RED);
+    at_120_fps.set_target(RED);
+    for _ in 0..15 {
+        at_30_fps.step(1.0 / 30.0);
+    }
+    for _ in 0..60 {
+        at_120_fps.step(1.0 / 120.0);
+    }
+    assert!((at_30_fps.progress() - at_120_fps.progress()).abs() < 1e-4);
+    assert!((at_30_fps.color.g - at_120_fps.color.g).abs() < 1e-3);
+}
+
+#[test]
+fn test_setting_target_color_restarts_from_current_color() {
+    let mut transition = transition(1.0, Easing::Linear);
+    transition.set_target(RED);
+    transition.step(0.5);
+    let halfway = transition.color;
+    transition.target_color = Color::BLACK;
+    transition.step(0.0);
+    assert_eq!(transition.progress(), 0.0);
+    assert!((transition.color.r - halfway.r).abs() < 1e-4);
+    assert!((transition.color.g - halfway.g).abs() < 1e-4);
+    assert!(transition.step(1.0));
+    assert_eq!(transition.color, Color::BLACK);
+}
+
+#[test]
+fn test_immediate_ends_transition() {
+    let mut transition = transition(1.0, Easing::Linear);
+    transition.set_target(RED);
+    transition.step(0.1);
+    transition.immediate();
+    assert_eq!(transition.color, RED);
+    assert!(!transition.step(0.1));
+}
+
+// ===== Interpolation Spaces =====
+
+/// Joy and sadness in the Plutchik mapping are nearly complementary
+const YELLOW: Color = Color::from_rgb(1.0, 0.9, 0.0);
+const BLUE: Color = Color::from_rgb(0.0, 0.3, 1.0);
+
+fn chroma(color: Color) -> f32 {
+    use palette::{FromColor, Oklch, Srgb};
+    Oklch::from_color(Srgb::new(color.r, color.g, color.b)).chroma
+}
+
+fn hsv(hue: f32) -> Color {
+    use palette::{FromColor, Hsv, Srgb};
+    let srgb = Srgb::from_color(Hsv::new(hue, 1.0, 1.0));
+    Color::from_rgb(srgb.red, srgb.green, srgb.blue)
+}
+
+fn assert_close(a: Color, b: Color) {
+    assert!(
+        (a.r - b.r).abs() < 1e-3 && (a.g - b.g).abs() < 1e-3 && (a.b - b.b).abs() < 1e-3,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+#[test]
+fn test_every_space_keeps_the_end_colors() {
+    for space in InterpolationSpace::ALL {
+        assert_close(space.mix(YELLOW, BLUE, 0.0), YELLOW);
+        assert_close(space.mix(YELLOW, BLUE, 1.0), BLUE);
+    }
+}
+
+#[test]
+fn test_oklch_midpoint_is_more_saturated_than_linear_rgb() {
+    let linear = InterpolationSpace::LinSrgb.mix(YELLOW, BLUE, 0.5);
+    let oklab = InterpolationSpace::Oklab.mix(YELLOW, BLUE, 0.5);
+    let oklch = InterpolationSpace::Oklch.mix(YELLOW, BLUE, 0.5);
+    assert!(chroma(oklch) > chroma(linear));
+    assert!(chroma(oklch) > chroma(oklab));
+}
+
+#[test]
+fn test_hue_takes_the_shorter_path() {
+    // Halfway between 350° and 10° is red, not cyan
+    let mixed = InterpolationSpace::Hsv.mix(hsv(350.0), hsv(10.0), 0.5);
+    assert_close(mixed, hsv(0.0));
+}
+
+#[test]
+fn test_gray_takes_the_hue_of_the_other_color() {
+    let gray = Color::from_rgb(0.5, 0.5, 0.5);
+    let mixed = InterpolationSpace::Hsv.mix(gray, hsv(120.0), 0.5);
+    assert!(mixed.g > mixed.r && mixed.g > mixed.b);
+}
+
+#[test]
+fn test_transition_uses_its_space() {
+    for space in InterpolationSpace::ALL {
+        let mut transition = ColorTransition::new(YELLOW);
+        transition.duration = 1.0;
+        transition.easing = Easing::Linear;
+        transition.space = space;
+        transition.set_target(BLUE);
+        transition.step(0.5);
+        assert_close(transition.color, space.mix(YELLOW, BLUE, 0.5));
+    }
+}
+
+// ===== Timelines =====
+
+/// White, then red after 1 second, then black after 3 seconds
+fn timeline(repeat: Repeat) -> ColorTimeline {
+    ColorTimeline::new(
+        vec![
+            Keyframe::new(RED, 1.0, Easing::Linear),
+            Keyframe::new(Color::WHITE, 0.0, Easing::Linear),
+            Keyframe::new(Color::BLACK, 3.0, Easing::Linear),
+        ],
+        repeat,
+    )
+}
+
+#[test]
+fn test_timeline_sorts_keyframes() {
+    let timeline = timeline(Repeat::Hold);
+    let times: Vec<f32> = timeline.keyframes().iter().map(|k| k.time).collect();
+    assert_eq!(times, vec![0.0, 1.0, 3.0]);
+    assert_eq!(timeline.duration(), 3.0);
+}
+
+#[test]
+fn test_timeline_passes_through_keyframes() {
+    let timeline = timeline(Repeat::Hold);
+    assert_eq!(timeline.color_at(0.0), Color::WHITE);
+    assert_eq!(timeline.color_at(1.0), RED);
+    assert_eq!(timeline.color_at(3.0), Color::BLACK);
+    let between = timeline.color_at(2.0);
+    assert!(between.r < 1.0 && between.r > 0.0);
+}
+
+#[test]
+fn test_timeline_holds_last_keyframe() {
+    let mut timeline = timeline(Repeat::Hold);
+    assert!(!timeline.step(2.0));
+    assert!(timeline.step(2.0));
+    assert!(timeline.is_finished());
+    assert_eq!(timeline.color(), Color::BLACK);
+    assert!(!timeline.step(1.0));
+    assert_eq!(timeline.color(), Color::BLACK);
+}
+
+#[test]
+fn test_timeline_loops() {
+    let mut timeline = timeline(Repeat::Loop);
+    assert_eq!(timeline.color_at(4.0), RED);
+    assert!(!timeline.step(2.5));
+    assert!(timeline.step(1.5));
+    assert!(!timeline.is_finished());
+    assert_eq!(timeline.elapsed(), 1.0);
+    assert_eq!(timeline.color(), RED);
+}
+
+#[test]
+fn test_timeline_ping_pongs() {
+    let mut timeline = timeline(Repeat::PingPong);
+    assert_eq!(timeline.color_at(5.0), RED);
+    assert_eq!(timeline.color_at(6.0), Color::WHITE);
+    assert!(!timeline.step(5.0));
+    assert!(timeline.step(2.0));
+    assert_eq!(timeline.color(), RED);
+}
+
+#[test]
+fn test_keyframe_easing_applies_to_segment_before_it() {
+    let timeline = ColorTimeline::new(
+        vec![
+            Keyframe::new(Color::BLACK, 0.0, Easing::Linear),
+            Keyframe::new(Color::WHITE, 1.0, Easing::Cubic),
+        ],
+        Repeat::Hold,
+    );
+    let linear = InterpolationSpace::LinSrgb.mix(Color::BLACK, Color::WHITE, 0.25);
+    let eased = timeline.color_at(0.25);
+    assert!(eased.r > linear.r);
+}
+
+#[test]
+fn test_empty_timeline_is_white_and_finished() {
+    let timeline = ColorTimeline::default();
+    assert_eq!(timeline.color(), Color::WHITE);
+    assert!(timeline.is_finished());
+}
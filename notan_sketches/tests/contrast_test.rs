@@ -0,0 +1,119 @@
+use notan::prelude::Color;
+use notan_sketches::colors::contrast::*;
+
+const DARK_BLUE: Color = Color::from_rgb(0.0, 0.0, 0.4);
+const YELLOW: Color = Color::from_rgb(1.0, 0.9, 0.0);
+
+fn gray(value: f32) -> Color {
+    Color::from_rgb(value, value, value)
+}
+
+// ===== WCAG =====
+
+#[test]
+fn test_relative_luminance_of_black_and_white() {
+    assert_eq!(relative_luminance(&Color::BLACK), 0.0);
+    assert!((relative_luminance(&Color::WHITE) - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_contrast_ratio_range() {
+    assert!((contrast_ratio(&Color::BLACK, &Color::WHITE) - 21.0).abs() < 1e-4);
+    assert_eq!(contrast_ratio(&YELLOW, &YELLOW), 1.0);
+    assert_eq!(
+        contrast_ratio(&Color::WHITE, &DARK_BLUE),
+        contrast_ratio(&DARK_BLUE, &Color::WHITE)
+    );
+}
+
+#[test]
+fn test_contrast_ratio_of_mid_gray_on_white() {
+    // #777777 is the classic example of just failing AA on white
+    let ratio = contrast_ratio(&gray(0x77 as f32 / 255.0), &Color::WHITE);
+    assert!((ratio - 4.48).abs() < 0.01);
+    assert!(ratio < WCAG_AA);
+}
+
+// ===== APCA =====
+
+#[test]
+fn test_apca_black_and_white() {
+    assert!((apca_contrast(&Color::BLACK, &Color::WHITE) - 106.04).abs() < 0.1);
+    assert!((apca_contrast(&Color::WHITE, &Color::BLACK) + 107.88).abs() < 0.1);
+}
+
+#[test]
+fn test_apca_gray_on_white() {
+    // Reference value for #888888 text on #ffffff
+    let lc = apca_contrast(&gray(0x88 as f32 / 255.0), &Color::WHITE);
+    assert!((lc - 63.06).abs() < 0.1);
+}
+
+#[test]
+fn test_apca_low_contrast_is_zero() {
+    assert_eq!(apca_contrast(&gray(0.5), &gray(0.5)), 0.0);
+    assert_eq!(apca_contrast(&gray(0.52), &gray(0.5)), 0.0);
+}
+
+// ===== Choosing Text Colors =====
+
+#[test]
+fn test_best_text_color_from_palette() {
+    let palette = [Color::BLACK, YELLOW, Color::WHITE];
+    let choice = best_text_color(&DARK_BLUE, &palette, ContrastMetric::Wcag).unwrap();
+    assert_eq!(choice.color, Color::WHITE);
+    assert_eq!(choice.contrast, contrast_ratio(&Color::WHITE, &DARK_BLUE));
+
+    // Yellow beats black on dark blue when white is not available
+    let choice = best_text_color(&DARK_BLUE, &palette[..2], ContrastMetric::Apca).unwrap();
+    assert_eq!(choice.color, YELLOW);
+}
+
+#[test]
+fn test_best_text_color_on_all_backgrounds_maximizes_lowest_contrast() {
+    // White is best on the dark blue, but unreadable on the light gray
+    let backgrounds = [DARK_BLUE, gray(0.9)];
+    let palette = [Color::WHITE, Color::BLACK, gray(0.35)];
+    let choice = best_text_color_on_all(&backgrounds, &palette, ContrastMetric::Wcag).unwrap();
+    let lowest = |color: &Color| {
+        backgrounds
+            .iter()
+            .map(|bg| contrast_ratio(color, bg))
+            .fold(f32::INFINITY, f32::min)
+    };
+    for candidate in palette.iter() {
+        assert!(choice.contrast >= lowest(candidate));
+    }
+    assert_eq!(choice.contrast, lowest(&choice.color));
+}
+
+#[test]
+fn test_no_candidates_or_backgrounds() {
+    assert_eq!(
+        best_text_color(&Color::WHITE, &[], ContrastMetric::Wcag),
+        None
+    );
+    assert_eq!(
+        best_text_color_on_all(&[], &[Color::BLACK], ContrastMetric::Wcag),
+        None
+    );
+}
+
+#[test]
+fn test_first_candidate_wins_ties() {
+    let choice = best_text_color(&gray(0.5), &[YELLOW, YELLOW], ContrastMetric::Wcag).unwrap();
+    assert_eq!(choice.color, YELLOW);
+    let choice = best_text_color(
+        &Color::WHITE,
+        &[Color::BLACK, Color::BLACK],
+        ContrastMetric::Apca,
+    );
+    assert_eq!(choice.unwrap().color, Color::BLACK);
+}
+
+#[test]
+fn test_composite() {
+    let color = composite(&Color::WHITE, 0.25, &Color::BLACK);
+    assert_eq!(color, gray(0.25));
+    assert_eq!(composite(&YELLOW, 1.0, &DARK_BLUE), YELLOW);
+}
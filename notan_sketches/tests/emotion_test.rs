@@ -0,0 +1,594 @@
+use notan_sketches::emotion::analyzer::{
+    split_paragraphs, split_paragraphs_by, Lexicon, LexiconError, ParagraphSplit, TextAnalyzer,
+};
+use notan_sketches::emotion::arc::EmotionArc;
+use notan_sketches::emotion::mapping::{
+    ColorMappingError, ColorMappingRegistry, ColorMappingTable,
+};
+use notan_sketches::emotion::*;
+
+const DICKINSON_DOC: &str = include_str!("../examples/assets/lb_dickinson01.json");
+
+// ===== Marker Parsing =====
+
+#[test]
+fn test_emotion_parses_from_marker() {
+    assert_eq!("joy".parse::<PlutchikEmotion>(), Ok(PlutchikEmotion::Joy));
+    assert_eq!(
+        "anticipation".parse::<PlutchikEmotion>(),
+        Ok(PlutchikEmotion::Anticipation)
+    );
+}
+
+#[test]
+fn test_emotion_round_trips_through_display() {
+    for emotion in PlutchikEmotion::ALL {
+        assert_eq!(emotion.to_string().parse::<PlutchikEmotion>(), Ok(emotion));
+    }
+}
+
+#[test]
+fn test_unknown_emotion_is_a_parse_error() {
+    let err = "joyy".parse::<PlutchikEmotion>().unwrap_err();
+    assert_eq!(err, ParseMarkerError("joyy".to_string()));
+    assert_eq!(err.to_string(), "unknown emotion marker `joyy`");
+}
+
+#[test]
+fn test_sentiment_markers_are_not_emotions() {
+    assert!("positive".parse::<PlutchikEmotion>().is_err());
+    assert_eq!(
+        "positive".parse::<EmocatMarker>(),
+        Ok(EmocatMarker::Positive)
+    );
+    assert_eq!(
+        "negative".parse::<EmocatMarker>(),
+        Ok(EmocatMarker::Negative)
+    );
+    assert_eq!(
+        "fear".parse::<EmocatMarker>(),
+        Ok(EmocatMarker::Emotion(PlutchikEmotion::Fear))
+    );
+}
+
+// ===== Serde =====
+
+#[test]
+fn test_score_deserializes_typed_marker() {
+    let score: EmocatAnalyzerScore =
+        serde_json::from_str(r#"{"marker": "sadness", "score": 0.25}"#).unwrap();
+    assert_eq!(
+        score.marker,
+        EmocatMarker::Emotion(PlutchikEmotion::Sadness)
+    );
+    assert_eq!(score.score, 0.25);
+}
+
+#[test]
+fn test_score_with_unknown_marker_fails_to_deserialize() {
+    let result: Result<EmocatAnalyzerScore, _> =
+        serde_json::from_str(r#"{"marker": "melancholy", "score": 0.25}"#);
+    let err = result.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("unknown emotion marker `melancholy`"));
+}
+
+#[test]
+fn test_score_serializes_marker_as_string() {
+    let score = EmocatAnalyzerScore {
+        marker: EmocatMarker::Emotion(PlutchikEmotion::Trust),
+        score: 0.5,
+    };
+    let json = serde_json::to_string(&score).unwrap();
+    assert_eq!(json, r#"{"marker":"trust","score":0.5}"#);
+}
+
+#[test]
+fn test_bundled_document_parses() {
+    let doc: EmocatOutputDoc = serde_json::from_str(DICKINSON_DOC).unwrap();
+    assert!(!doc.analyses.is_empty());
+}
+
+// ===== Color Mapping =====
+
+#[test]
+fn test_every_emotion_maps_to_a_saturated_plutchik_color() {
+    for emotion in PlutchikEmotion::ALL {
+        let emocolor = get_mapped_emocolor(emotion, &ColorMapping::PLUTCHIK);
+        assert_eq!(emocolor.emotion, Some(emotion));
+        assert!(emocolor.hsv.saturation > 0.0, "{} mapped to gray", emotion);
+    }
+}
+
+#[test]
+fn test_neutral_emocolor_has_no_emotion() {
+    let emocolor = get_neutral_emocolor();
+    assert_eq!(emocolor.emotion, None);
+    assert_eq!(emocolor.hsv.saturation, 0.0);
+}
+
+// ===== TopEmotionsModel =====
+
+fn analysis_with_scores(scores: &[(&str, f32)]) -> EmocatTextAnalysis {
+    EmocatTextAnalysis {
+        text: "Test paragraph".to_string(),
+        meta: EmocatMeta {
+            paragraph: 1,
+            from_line: 1,
+            to_line: 1,
+            file: "test.txt".to_string(),
+        },
+        results: EmocatAnalyzerResults {
+            nrclex: scores
+                .iter()
+                .map(|(marker, score)| EmocatAnalyzerScore {
+                    marker: marker.parse().unwrap(),
+                    score: *score,
+                })
+                .collect(),
+            t2e_repo: vec![],
+            t2e_demo: vec![],
+        },
+    }
+}
+
+#[test]
+fn test_model_keeps_tied_top_emotions() {
+    let analysis = analysis_with_scores(&[
+        ("positive", 0.5),
+        ("negative", 0.1),
+        ("joy", 0.3),
+        ("fear", 0.1),
+        ("trust", 0.3),
+    ]);
+    let model = TopEmotionsModel::try_from_analysis(&analysis).unwrap();
+    assert_eq!(model.positive, 0.5);
+    assert_eq!(model.negative, 0.1);
+    let top: Vec<PlutchikEmotion> = model.top_emotions.iter().map(|s| s.emotion).collect();
+    assert_eq!(top, vec![PlutchikEmotion::Joy, PlutchikEmotion::Trust]);
+}
+
+#[test]
+fn test_model_missing_sentiment_is_an_error() {
+    let analysis = analysis_with_scores(&[("positive", 0.5), ("joy", 0.3)]);
+    assert_eq!(
+        TopEmotionsModel::try_from_analysis(&analysis).err(),
+        Some(EmotionModelError::MissingSentiment(EmocatMarker::Negative))
+    );
+}
+
+#[test]
+fn test_model_without_emotions_is_an_error() {
+    let analysis = analysis_with_scores(&[("positive", 0.5), ("negative", 0.1)]);
+    assert_eq!(
+        TopEmotionsModel::try_from_analysis(&analysis).err(),
+        Some(EmotionModelError::EmptyScores)
+    );
+}
+
+#[test]
+fn test_model_with_nan_score_is_an_error() {
+    let analysis = analysis_with_scores(&[("positive", 0.5), ("negative", 0.1), ("joy", f32::NAN)]);
+    match TopEmotionsModel::try_from_analysis(&analysis) {
+        Err(EmotionModelError::InvalidScore { marker, score }) => {
+            assert_eq!(marker, EmocatMarker::Emotion(PlutchikEmotion::Joy));
+            assert!(score.is_nan());
+        }
+        _ => panic!("expected an InvalidScore error"),
+    }
+}
+
+#[test]
+fn test_model_builds_for_every_bundled_paragraph() {
+    let doc: EmocatOutputDoc = serde_json::from_str(DICKINSON_DOC).unwrap();
+    for analysis in doc.analyses.iter() {
+        assert!(TopEmotionsModel::try_from_analysis(analysis).is_ok());
+    }
+}
+
+// ===== Blending =====
+
+fn chroma(color: notan::prelude::Color) -> f32 {
+    use palette::{FromColor, Oklch, Srgb};
+    Oklch::from_color(Srgb::new(color.r, color.g, color.b)).chroma
+}
+
+#[test]
+fn test_model_keeps_all_scores_highest_first() {
+    let analysis = analysis_with_scores(&[
+        ("positive", 0.5),
+        ("negative", 0.1),
+        ("fear", 0.1),
+        ("joy", 0.3),
+        ("trust", 0.2),
+    ]);
+    let model = TopEmotionsModel::try_from_analysis(&analysis).unwrap();
+    let emotions: Vec<PlutchikEmotion> = model.scores.iter().map(|s| s.emotion).collect();
+    assert_eq!(
+        emotions,
+        vec![
+            PlutchikEmotion::Joy,
+            PlutchikEmotion::Trust,
+            PlutchikEmotion::Fear
+        ]
+    );
+}
+
+#[test]
+fn test_entropy_is_normalized() {
+    let single = analysis_with_scores(&[("positive", 0.5), ("negative", 0.1), ("joy", 1.0)]);
+    let model = TopEmotionsModel::try_from_analysis(&single).unwrap();
+    assert_eq!(model.get_entropy(), 0.0);
+
+    let mut even = vec![("positive", 0.5), ("negative", 0.1)];
+    even.extend(PlutchikEmotion::ALL.iter().map(|e| (e.as_str(), 0.1)));
+    let model = TopEmotionsModel::try_from_analysis(&analysis_with_scores(&even)).unwrap();
+    assert!((model.get_entropy() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_weighted_blend_reflects_secondary_emotions() {
+    let analysis = analysis_with_scores(&[
+        ("positive", 0.5),
+        ("negative", 0.1),
+        ("joy", 0.6),
+        ("sadness", 0.4),
+    ]);
+    let model = TopEmotionsModel::try_from_analysis(&analysis).unwrap();
+    let mapping = ColorMapping::PLUTCHIK;
+    let top_only = model.get_blended_color(&mapping, BlendStrategy::TopK(1));
+    let weighted = model.get_blended_color(&mapping, BlendStrategy::Weighted);
+    assert_ne!(top_only, weighted);
+}
+
+#[test]
+fn test_entropy_aware_blend_is_less_saturated() {
+    let analysis = analysis_with_scores(&[
+        ("positive", 0.5),
+        ("negative", 0.1),
+        ("joy", 0.5),
+        ("trust", 0.4),
+        ("anticipation", 0.3),
+    ]);
+    let model = TopEmotionsModel::try_from_analysis(&analysis).unwrap();
+    let mapping = ColorMapping::PLUTCHIK;
+    let weighted = model.get_blended_color(&mapping, BlendStrategy::Weighted);
+    let muted = model.get_blended_color(&mapping, BlendStrategy::EntropyAware);
+    assert!(chroma(muted) < chroma(weighted));
+}
+
+#[test]
+fn test_blend_without_scoring_emotions_is_gray() {
+    let analysis = analysis_with_scores(&[("positive", 0.0), ("negative", 0.0), ("joy", 0.0)]);
+    let model = TopEmotionsModel::try_from_analysis(&analysis).unwrap();
+    let color = model.get_blended_color(&ColorMapping::PLUTCHIK, BlendStrategy::Weighted);
+    assert_eq!(color, notan::prelude::Color::GRAY);
+}
+
+// ===== Analyzer Sources =====
+
+fn scores(pairs: &[(&str, f32)]) -> Vec<EmocatAnalyzerScore> {
+    pairs
+        .iter()
+        .map(|(marker, score)| EmocatAnalyzerScore {
+            marker: marker.parse().unwrap(),
+            score: *score,
+        })
+        .collect()
+}
+
+fn score_of(scores: &[EmocatAnalyzerScore], marker: &str) -> f32 {
+    let marker: EmocatMarker = marker.parse().unwrap();
+    scores.iter().find(|s| s.marker == marker).unwrap().score
+}
+
+#[test]
+fn test_scores_for_selects_analyzer() {
+    let results = EmocatAnalyzerResults {
+        nrclex: scores(&[("joy", 1.0)]),
+        t2e_repo: scores(&[("fear", 1.0)]),
+        t2e_demo: scores(&[("anger", 1.0)]),
+    };
+    assert_eq!(
+        results.scores_for(AnalyzerSource::T2eRepo)[0].marker,
+        EmocatMarker::Emotion(PlutchikEmotion::Fear)
+    );
+    assert_eq!(
+        results.scores_for(AnalyzerSource::T2eDemo)[0].marker,
+        EmocatMarker::Emotion(PlutchikEmotion::Anger)
+    );
+}
+
+#[test]
+fn test_ensemble_normalizes_and_averages_emotions() {
+    let results = EmocatAnalyzerResults {
+        nrclex: scores(&[
+            ("positive", 0.5),
+            ("negative", 0.0),
+            ("joy", 0.5),
+            ("fear", 0.0),
+        ]),
+        // A different scale: totals 4.0
+        t2e_repo: scores(&[
+            ("positive", 0.0),
+            ("negative", 0.0),
+            ("joy", 2.0),
+            ("fear", 2.0),
+        ]),
+        t2e_demo: vec![],
+    };
+    let ensemble = results.scores_for(AnalyzerSource::Ensemble);
+    assert!((score_of(&ensemble, "joy") - 0.75).abs() < 1e-6);
+    assert!((score_of(&ensemble, "fear") - 0.25).abs() < 1e-6);
+    // Only nrclex reports sentiment, so it isn't diluted by t2e_repo
+    assert!((score_of(&ensemble, "positive") - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_ensemble_normalizes_sentiment_apart_from_emotions() {
+    let results = EmocatAnalyzerResults {
+        // Half of the nrclex total is sentiment
+        nrclex: scores(&[
+            ("positive", 0.3),
+            ("negative", 0.2),
+            ("joy", 0.5),
+            ("fear", 0.0),
+        ]),
+        t2e_repo: scores(&[
+            ("positive", 0.0),
+            ("negative", 0.0),
+            ("joy", 0.0),
+            ("fear", 1.0),
+        ]),
+        t2e_demo: vec![],
+    };
+    let ensemble = results.scores_for(AnalyzerSource::Ensemble);
+    // Each analyzer found a single emotion, and they weigh the same
+    assert!((score_of(&ensemble, "joy") - 0.5).abs() < 1e-6);
+    assert!((score_of(&ensemble, "fear") - 0.5).abs() < 1e-6);
+    assert!((score_of(&ensemble, "positive") - 0.6).abs() < 1e-6);
+    assert!((score_of(&ensemble, "negative") - 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn test_model_from_bundled_ensemble() {
+    let doc: EmocatOutputDoc = serde_json::from_str(DICKINSON_DOC).unwrap();
+    for analysis in doc.analyses.iter() {
+        assert!(TopEmotionsModel::try_from_source(analysis, AnalyzerSource::Ensemble).is_ok());
+    }
+}
+
+// ===== Color Mapping Tables =====
+
+#[test]
+fn test_bundled_mapping_files_are_valid() {
+    let pastel = ColorMappingTable::from_toml_str(include_str!(
+        "../examples/assets/color_mappings/pastel.toml"
+    ))
+    .unwrap();
+    assert_eq!(pastel.name, "Pastel");
+    let warm_cool = ColorMappingTable::from_json_str(include_str!(
+        "../examples/assets/color_mappings/warm_cool.json"
+    ))
+    .unwrap();
+    assert_eq!(warm_cool.name, "Warm & Cool");
+}
+
+#[test]
+fn test_mapping_missing_emotions_fails_validation() {
+    let source = r#"{
+        "name": "Partial",
+        "emotions": {
+            "joy": { "hue": 55.0, "saturation": 1.0, "value": 1.0, "sentiment": "positive" }
+        }
+    }"#;
+    match ColorMappingTable::from_json_str(source) {
+        Err(ColorMappingError::MissingEmotions(missing)) => {
+            assert_eq!(missing.len(), 7);
+            assert!(!missing.contains(&PlutchikEmotion::Joy));
+        }
+        _ => panic!("expected a MissingEmotions error"),
+    }
+}
+
+#[test]
+fn test_mapping_with_unknown_emotion_fails_to_parse() {
+    let source = r#"{
+        "name": "Typo",
+        "emotions": {
+            "joyy": { "hue": 55.0, "saturation": 1.0, "value": 1.0, "sentiment": "positive" }
+        }
+    }"#;
+    assert!(matches!(
+        ColorMappingTable::from_json_str(source),
+        Err(ColorMappingError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_registry_selects_custom_mapping_by_name() {
+    let mut registry = ColorMappingRegistry::default();
+    let table = ColorMappingTable::from_toml_str(include_str!(
+        "../examples/assets/color_mappings/pastel.toml"
+    ))
+    .unwrap();
+    registry.register(table);
+    assert_eq!(registry.names(), vec!["Plutchik", "Art Therapy", "Pastel"]);
+
+    let tables = registry.tables();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].name, "Pastel");
+
+    let mapping = registry.get("Pastel").unwrap();
+    let emocolor = get_mapped_emocolor(PlutchikEmotion::Joy, mapping);
+    assert_eq!(emocolor.hsv.saturation, 0.4);
+    assert_eq!(emocolor.sentiment, Sentiment::POSITIVE);
+}
+
+// ===== Emotion Arc =====
+
+fn doc_with_paragraphs(paragraphs: &[&[(&str, f32)]]) -> EmocatOutputDoc {
+    EmocatOutputDoc {
+        title: "Test".to_string(),
+        author: "Tester".to_string(),
+        analyses: paragraphs.iter().map(|p| analysis_with_scores(p)).collect(),
+    }
+}
+
+#[test]
+fn test_arc_finds_peaks_and_turning_points() {
+    let doc = doc_with_paragraphs(&[
+        &[("positive", 0.1), ("negative", 0.0), ("joy", 0.1)],
+        &[("positive", 0.5), ("negative", 0.0), ("joy", 0.9)],
+        &[("positive", 0.2), ("negative", 0.0), ("joy", 0.2)],
+        &[("positive", 0.0), ("negative", 0.4), ("sadness", 0.3)],
+        &[("positive", 0.0), ("negative", 0.1), ("sadness", 0.1)],
+    ]);
+    let arc = EmotionArc::from_doc_with_smoothing(&doc, AnalyzerSource::Nrclex, 0);
+    assert_eq!(arc.len(), 5);
+    assert_eq!(arc.peaks(), &[1, 3]);
+    assert_eq!(arc.turning_points(), &[1, 3]);
+    assert_eq!(arc.next_peak(1), Some(3));
+    assert!(arc.trend(1) < 0.0);
+    assert!((arc.point(3).unwrap().valence + 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn test_arc_smoothing_averages_neighbours() {
+    let doc = doc_with_paragraphs(&[
+        &[("positive", 0.0), ("negative", 0.0), ("joy", 0.0)],
+        &[("positive", 0.9), ("negative", 0.0), ("joy", 0.9)],
+        &[("positive", 0.0), ("negative", 0.0), ("joy", 0.0)],
+    ]);
+    let arc = EmotionArc::from_doc(&doc, AnalyzerSource::Nrclex);
+    let point = arc.point(1).unwrap();
+    assert_eq!(point.valence, 0.9);
+    assert!((point.smoothed_valence - 0.3).abs() < 1e-6);
+    assert!((arc.point(0).unwrap().smoothed_valence - 0.45).abs() < 1e-6);
+}
+
+#[test]
+fn test_arc_keeps_unanalyzable_paragraphs_as_neutral() {
+    let doc = doc_with_paragraphs(&[
+        &[("positive", 0.5), ("negative", 0.0), ("joy", 0.5)],
+        &[("positive", 0.5), ("joy", 0.5)],
+    ]);
+    let arc = EmotionArc::from_doc(&doc, AnalyzerSource::Nrclex);
+    let point = arc.point(1).unwrap();
+    assert!(point.model.is_err());
+    assert_eq!(point.intensity, 0.0);
+    let fingerprint = arc.fingerprint(&ColorMapping::PLUTCHIK, BlendStrategy::Weighted);
+    assert_eq!(fingerprint.len(), 2);
+    assert_eq!(fingerprint[1], notan::prelude::Color::GRAY);
+}
+
+#[test]
+fn test_arc_from_bundled_doc() {
+    let doc: EmocatOutputDoc = serde_json::from_str(DICKINSON_DOC).unwrap();
+    let arc = EmotionArc::from_doc(&doc, AnalyzerSource::Ensemble);
+    assert_eq!(arc.len(), doc.analyses.len());
+    assert!(arc.point(doc.analyses.len()).is_none());
+}
+
+// ===== Text Analyzer =====
+
+#[test]
+fn test_split_paragraphs_matches_emocat_meta() {
+    let doc: EmocatOutputDoc = serde_json::from_str(DICKINSON_DOC).unwrap();
+    let texts: Vec<&str> = doc.analyses.iter().map(|a| a.text.as_str()).collect();
+    let paragraphs = split_paragraphs(&texts.join("\n\n"));
+    assert_eq!(paragraphs.len(), doc.analyses.len());
+    for (paragraph, analysis) in paragraphs.iter().zip(doc.analyses.iter()) {
+        assert_eq!(paragraph.text, analysis.text);
+        assert_eq!(paragraph.from_line, analysis.meta.from_line);
+        assert_eq!(paragraph.to_line, analysis.meta.to_line);
+    }
+}
+
+#[test]
+fn test_split_paragraphs_skips_repeated_blank_lines() {
+    let paragraphs = split_paragraphs("\nOne\nTwo\n\n  \n\nThree\n");
+    assert_eq!(paragraphs.len(), 2);
+    assert_eq!(paragraphs[0].text, "One\nTwo");
+    assert_eq!((paragraphs[0].from_line, paragraphs[0].to_line), (2, 3));
+    assert_eq!((paragraphs[1].from_line, paragraphs[1].to_line), (7, 7));
+}
+
+#[test]
+fn test_split_paragraphs_by_line_rules() {
+    let text = "One\nTwo\nThree\n\nFour";
+    let lines = split_paragraphs_by(text, ParagraphSplit::Lines);
+    assert_eq!(lines.len(), 4);
+    assert_eq!((lines[3].from_line, lines[3].to_line), (5, 5));
+
+    let pairs = split_paragraphs_by(text, ParagraphSplit::FixedLines(2));
+    let texts: Vec<&str> = pairs.iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(texts, vec!["One\nTwo", "Three", "Four"]);
+    assert_eq!((pairs[1].from_line, pairs[1].to_line), (3, 3));
+}
+
+#[test]
+fn test_lexicon_scores_affect_frequencies() {
+    let lexicon =
+        Lexicon::from_nrc_str("joy\tjoy\t1\njoy\tpositive\t1\njoy\tfear\t0\ngloom\tsadness\t1\n")
+            .unwrap();
+    let scores = lexicon.score("Joy, joy and gloom!");
+    assert_eq!(scores.len(), 10);
+    assert_eq!(score_of(&scores, "joy"), 0.4);
+    assert_eq!(score_of(&scores, "positive"), 0.4);
+    assert_eq!(score_of(&scores, "sadness"), 0.2);
+    assert_eq!(score_of(&scores, "fear"), 0.0);
+}
+
+#[test]
+fn test_lexicon_falls_back_to_singular() {
+    let lexicon = Lexicon::from_nrc_str("star\tanticipation\t1").unwrap();
+    assert_eq!(lexicon.lookup("Stars").len(), 1);
+    assert_eq!(lexicon.lookup("star’s").len(), 0);
+    assert!(lexicon
+        .score("no matches here")
+        .iter()
+        .all(|s| s.score == 0.0));
+}
+
+#[test]
+fn test_lexicon_rejects_invalid_entries() {
+    assert_eq!(
+        Lexicon::from_nrc_str("# comment\njoy\tjoy").err(),
+        Some(LexiconError::InvalidLine(2))
+    );
+    assert_eq!(
+        Lexicon::from_nrc_str("joy\tbliss\t1").err(),
+        Some(LexiconError::UnknownAffect {
+            line: 1,
+            affect: "bliss".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_analyzer_output_drives_the_model() {
+    let analyzer = TextAnalyzer::default();
+    assert!(!analyzer.lexicon.is_empty());
+    let doc = analyzer.analyze(
+        "Test",
+        "Tester",
+        "test.txt",
+        "The happy sun shines on the garden.\n\nIn the dark night I weep alone.",
+    );
+    let json = serde_json::to_string(&doc).unwrap();
+    let doc: EmocatOutputDoc = serde_json::from_str(&json).unwrap();
+    assert_eq!(doc.analyses.len(), 2);
+    assert_eq!(doc.analyses[1].meta.paragraph, 2);
+
+    let bright = TopEmotionsModel::try_from_analysis(&doc.analyses[0]).unwrap();
+    assert!(bright
+        .top_emotions
+        .iter()
+        .any(|s| s.emotion == PlutchikEmotion::Joy));
+    assert!(bright.positive > bright.negative);
+    let dark =
+        TopEmotionsModel::try_from_source(&doc.analyses[1], AnalyzerSource::Ensemble).unwrap();
+    assert!(dark.negative > dark.positive);
+}
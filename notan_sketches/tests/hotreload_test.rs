@@ -0,0 +1,90 @@
+use notan_sketches::hotreload::*;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A file in a directory of its own under the system's temp directory
+fn temp_file(test: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("notan_sketches_{}", test));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("palette.txt");
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Rewrites the file with a later modification time, as file systems may not tell apart
+/// writes made in quick succession
+fn modify(path: &Path, contents: &str, seconds_later: u64) {
+    fs::write(path, contents).unwrap();
+    let file = File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(seconds_later))
+        .unwrap();
+}
+
+fn load_text(path: &Path) -> Result<String, String> {
+    let bytes = read_asset(path)?;
+    let text = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+    if text.is_empty() {
+        return Err("empty palette".to_string());
+    }
+    Ok(text)
+}
+
+// ===== Watching files =====
+
+#[test]
+fn test_unmodified_files_are_not_changed() {
+    let path = temp_file("unmodified", "red");
+    let mut watcher = FileWatcher::new(&[&path]);
+    assert!(!watcher.poll());
+    assert!(!watcher.poll());
+}
+
+#[test]
+fn test_modified_file_is_changed_once() {
+    let path = temp_file("modified", "red");
+    let mut watcher = FileWatcher::new(&[&path]);
+    modify(&path, "blue", 10);
+    assert!(watcher.poll());
+    assert!(!watcher.poll());
+}
+
+#[test]
+fn test_missing_file_is_changed_when_created() {
+    let path = temp_file("missing", "red");
+    fs::remove_file(&path).unwrap();
+    let mut watcher = FileWatcher::new(&[&path]);
+    assert!(!watcher.poll());
+    fs::write(&path, "green").unwrap();
+    assert!(watcher.poll());
+}
+
+// ===== Hot assets =====
+
+#[test]
+fn test_asset_is_reloaded_when_modified() {
+    let path = temp_file("reloaded", "red");
+    let mut palette = HotAsset::load(&[&path], || load_text(&path)).unwrap();
+    assert!(!palette.update(|| load_text(&path)));
+    modify(&path, "blue", 10);
+    assert!(palette.update(|| load_text(&path)));
+    assert_eq!(palette.asset, "blue");
+}
+
+#[test]
+fn test_asset_that_fails_to_reload_is_kept() {
+    let path = temp_file("failed", "red");
+    let mut palette = HotAsset::load(&[&path], || load_text(&path)).unwrap();
+    modify(&path, "", 10);
+    assert!(!palette.update(|| load_text(&path)));
+    assert_eq!(palette.asset, "red");
+    modify(&path, "green", 20);
+    assert!(palette.update(|| load_text(&path)));
+    assert_eq!(palette.asset, "green");
+}
+
+#[test]
+fn test_missing_asset_errors_with_its_path() {
+    let err = read_asset("no/such/palette.txt").unwrap_err();
+    assert!(err.starts_with("no/such/palette.txt: "), "{}", err);
+}
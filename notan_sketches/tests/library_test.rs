@@ -0,0 +1,140 @@
+use notan_sketches::emotion::EmocatOutputDoc;
+use notan_sketches::emotion_bg_visualizer::library::{
+    is_color_mapping_file, parse_document, DocumentError, DocumentLibrary,
+};
+use notan_sketches::emotion_bg_visualizer::settings::ReadPosition;
+use notan_sketches::persistence;
+use std::sync::{Mutex, MutexGuard};
+
+const DICKINSON_DOC: &str = include_str!("../examples/assets/lb_dickinson01.json");
+
+// ===== Parsing Documents =====
+
+#[test]
+fn test_parse_bundled_emocat_document() {
+    let doc = parse_document("lb_dickinson01.json", DICKINSON_DOC.as_bytes()).unwrap();
+    assert_eq!(doc.author, "Emily Dickinson");
+    assert_eq!(doc.analyses.len(), 4);
+}
+
+#[test]
+fn test_parse_plain_text_document() {
+    let doc = parse_document("my poem.txt", b"A happy line\n\nA sad line\n").unwrap();
+    assert_eq!(doc.title, "my poem");
+    assert_eq!(doc.analyses.len(), 2);
+}
+
+#[test]
+fn test_malformed_json_reports_position() {
+    let err = parse_document("broken.json", b"{\n  \"title\": \"Broken\",\n}").unwrap_err();
+    match &err {
+        DocumentError::Json { file_name, .. } => assert_eq!(file_name, "broken.json"),
+        _ => panic!("expected a Json error"),
+    }
+    assert!(err.to_string().contains("line 3"));
+}
+
+#[test]
+fn test_document_without_paragraphs_is_an_error() {
+    let json = br#"{"title": "Empty", "author": "Nobody", "analyses": []}"#;
+    assert_eq!(
+        parse_document("empty.json", json).err(),
+        Some(DocumentError::NoParagraphs {
+            file_name: "empty.json".to_string()
+        })
+    );
+    assert_eq!(
+        parse_document("blank.txt", b"\n\n").err(),
+        Some(DocumentError::NoParagraphs {
+            file_name: "blank.txt".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_binary_file_is_not_text() {
+    assert_eq!(
+        parse_document("image.png", &[0x89, 0x50, 0xff, 0xfe]).err(),
+        Some(DocumentError::NotText {
+            file_name: "image.png".to_string()
+        })
+    );
+}
+
+// ===== Library =====
+
+/// Held by tests that save the library, which is kept under `XDG_DATA_HOME`
+static DATA_DIR: Mutex<()> = Mutex::new(());
+
+/// Keeps the saved library out of the user's data directory, in a directory of its own for
+/// each test
+fn use_temp_data_dir(test: &str) -> MutexGuard<'static, ()> {
+    let guard = DATA_DIR.lock().unwrap_or_else(|err| err.into_inner());
+    std::env::set_var(
+        "XDG_DATA_HOME",
+        std::env::temp_dir().join(format!("library_test_{}_{}", std::process::id(), test)),
+    );
+    guard
+}
+
+fn user_doc(title: &str, paragraphs: usize) -> EmocatOutputDoc {
+    let text = vec!["A happy line"; paragraphs].join("\n\n");
+    parse_document(&format!("{}.txt", title), text.as_bytes()).unwrap()
+}
+
+#[test]
+fn test_reading_position_survives_removing_an_earlier_document() {
+    let _data_dir = use_temp_data_dir("reading_position");
+    let bundled = vec![parse_document("lb_dickinson01.json", DICKINSON_DOC.as_bytes()).unwrap()];
+    let mut library = DocumentLibrary::new(bundled);
+    library.add(user_doc("first", 2));
+    let second = library.add(user_doc("second", 3));
+    let position = ReadPosition::new(&library.docs()[second], 3);
+
+    // Opening by the old index would now be out of range
+    library.remove(1);
+    assert_eq!(library.docs().len(), 2);
+    assert_eq!(position.locate(library.docs()), Some((1, 3)));
+
+    library.remove(1);
+    assert_eq!(position.locate(library.docs()), None);
+
+    // Bundled documents stay put
+    library.remove(0);
+    assert_eq!(library.docs().len(), 1);
+}
+
+#[test]
+fn test_saved_documents_that_dont_parse_are_skipped() {
+    let _data_dir = use_temp_data_dir("skip_saved");
+    let saved = serde_json::json!([
+        user_doc("first", 2),
+        { "title": "no analyses" },
+        user_doc("second", 1),
+    ]);
+    persistence::save_string("emo_bg_visualizer_library", &saved.to_string()).unwrap();
+
+    let library = DocumentLibrary::new(vec![]);
+    let titles: Vec<&str> = library.docs().iter().map(|d| d.title.as_str()).collect();
+    assert_eq!(titles, ["first", "second"]);
+}
+
+// ===== Color Mapping Files =====
+
+#[test]
+fn test_color_mapping_files_are_told_apart_from_documents() {
+    let pastel = include_str!("../examples/assets/color_mappings/pastel.toml");
+    let warm_cool = include_str!("../examples/assets/color_mappings/warm_cool.json");
+    assert!(is_color_mapping_file("pastel.toml", pastel.as_bytes()));
+    assert!(is_color_mapping_file(
+        "warm_cool.json",
+        warm_cool.as_bytes()
+    ));
+    assert!(!is_color_mapping_file(
+        "lb_dickinson01.json",
+        DICKINSON_DOC.as_bytes()
+    ));
+    assert!(!is_color_mapping_file("my poem.txt", b"{\"emotions\": {}}"));
+    // Left for parse_document() to report
+    assert!(!is_color_mapping_file("broken.json", b"{"));
+}
@@ -0,0 +1,120 @@
+use notan::prelude::Color;
+use notan_sketches::emotion_bg_visualizer::options::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Circle,
+    #[serde(rename = "Rounded Square")]
+    RoundedSquare,
+}
+
+impl OptionChoice for Shape {
+    fn all() -> &'static [Self] {
+        &[Shape::Circle, Shape::RoundedSquare]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Shape::Circle => "Circle",
+            Shape::RoundedSquare => "Rounded Square",
+        }
+    }
+}
+
+struct Settings {
+    shape: Shape,
+    size: f32,
+    outline: bool,
+    tint: Color,
+}
+
+impl Settings {
+    fn new() -> Self {
+        Self {
+            shape: Shape::Circle,
+            size: 1.0,
+            outline: false,
+            tint: Color::WHITE,
+        }
+    }
+
+    fn options(&mut self) -> Vec<VisualizerOption<'_>> {
+        vec![
+            VisualizerOption::choice("shape", "Shape", &mut self.shape),
+            VisualizerOption::range("size", "Size", &mut self.size, 0.5..=4.0, " px"),
+            VisualizerOption::toggle("outline", "Outline", &mut self.outline),
+            VisualizerOption::color("tint", "Tint", &mut self.tint),
+        ]
+    }
+}
+
+// ===== Choices =====
+
+#[test]
+fn test_choice_field_selects_by_index() {
+    let mut shape = Shape::Circle;
+    let field: &mut dyn ChoiceField = &mut shape;
+    assert_eq!(field.labels(), vec!["Circle", "Rounded Square"]);
+    assert_eq!(field.selected(), 0);
+    field.select(1);
+    assert_eq!(field.selected(), 1);
+    field.select(5);
+    assert_eq!(shape, Shape::RoundedSquare);
+}
+
+// ===== Saving =====
+
+#[test]
+fn test_options_are_saved_by_name() {
+    let mut settings = Settings::new();
+    settings.shape = Shape::RoundedSquare;
+    let values = save_options(&settings.options());
+    assert_eq!(values["shape"], json!("Rounded Square"));
+    assert_eq!(values["size"], json!(1.0));
+    assert_eq!(values["outline"], json!(false));
+    assert_eq!(values["tint"], json!([1.0, 1.0, 1.0, 1.0]));
+}
+
+#[test]
+fn test_options_round_trip() {
+    let mut settings = Settings::new();
+    settings.shape = Shape::RoundedSquare;
+    settings.size = 2.5;
+    settings.outline = true;
+    settings.tint = Color::new(0.5, 0.25, 0.0, 1.0);
+    let values = save_options(&settings.options());
+
+    let mut restored = Settings::new();
+    restore_options(&mut restored.options(), &values);
+    assert_eq!(restored.shape, Shape::RoundedSquare);
+    assert_eq!(restored.size, 2.5);
+    assert!(restored.outline);
+    assert_eq!(restored.tint, settings.tint);
+}
+
+// ===== Restoring =====
+
+#[test]
+fn test_missing_and_invalid_values_are_ignored() {
+    let values = OptionValues::from_iter([
+        ("shape".to_string(), json!("Triangle")),
+        ("outline".to_string(), json!("yes")),
+        ("tint".to_string(), json!([0.5, 0.5])),
+    ]);
+    let mut settings = Settings::new();
+    restore_options(&mut settings.options(), &values);
+    assert_eq!(settings.shape, Shape::Circle);
+    assert_eq!(settings.size, 1.0);
+    assert!(!settings.outline);
+    assert_eq!(settings.tint, Color::WHITE);
+}
+
+#[test]
+fn test_restored_range_is_clamped() {
+    let values = OptionValues::from_iter([("size".to_string(), json!(100))]);
+    let mut settings = Settings::new();
+    restore_options(&mut settings.options(), &values);
+    assert_eq!(settings.size, 4.0);
+}
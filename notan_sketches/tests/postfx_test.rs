@@ -0,0 +1,37 @@
+use notan_sketches::postfx::lut_pixels;
+
+const SIZE: u32 = 4;
+
+/// RGBA of the LUT pixel at `x`, `y`
+fn pixel(pixels: &[u8], x: u32, y: u32) -> [u8; 4] {
+    let start = ((y * SIZE * SIZE + x) * 4) as usize;
+    pixels[start..start + 4].try_into().unwrap()
+}
+
+// ===== Color grading LUT =====
+
+#[test]
+fn test_lut_is_a_strip_of_slices() {
+    let pixels = lut_pixels(SIZE, |rgb| rgb);
+    assert_eq!(pixels.len(), (SIZE * SIZE * SIZE * 4) as usize);
+}
+
+#[test]
+fn test_identity_lut_lays_out_the_color_cube() {
+    let pixels = lut_pixels(SIZE, |rgb| rgb);
+    assert_eq!(pixel(&pixels, 0, 0), [0, 0, 0, 255]);
+    // Red across a slice
+    assert_eq!(pixel(&pixels, 3, 0), [255, 0, 0, 255]);
+    // Green down it
+    assert_eq!(pixel(&pixels, 0, 3), [0, 255, 0, 255]);
+    // Blue from slice to slice
+    assert_eq!(pixel(&pixels, 4, 0), [0, 0, 85, 255]);
+    assert_eq!(pixel(&pixels, 15, 3), [255, 255, 255, 255]);
+}
+
+#[test]
+fn test_lut_grades_and_clamps_colors() {
+    let warm = lut_pixels(SIZE, |[r, g, b]| [r + 0.5, g, b - 0.5]);
+    assert_eq!(pixel(&warm, 0, 0), [128, 0, 0, 255]);
+    assert_eq!(pixel(&warm, 15, 3), [255, 255, 128, 255]);
+}
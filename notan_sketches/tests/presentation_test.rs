@@ -0,0 +1,43 @@
+use notan_sketches::emotion_bg_visualizer::presentation::*;
+
+// ===== Reading time =====
+
+#[test]
+fn test_reading_time_follows_word_count() {
+    assert_eq!(reading_time(60, 120.0), 30.0);
+    assert_eq!(reading_time(60, 60.0), 60.0);
+}
+
+#[test]
+fn test_short_pages_are_shown_for_min_time() {
+    assert_eq!(reading_time(0, DEFAULT_PRESENTATION_WPM), MIN_PAGE_SECONDS);
+    assert_eq!(reading_time(2, DEFAULT_PRESENTATION_WPM), MIN_PAGE_SECONDS);
+}
+
+// ===== Page turns =====
+
+#[test]
+fn test_page_turns_after_reading_time() {
+    let mut presentation = Presentation::default();
+    assert!(!presentation.step(29.0, 60, true));
+    assert!(presentation.step(1.0, 60, true));
+    // Timing starts over for the next page
+    assert!(!presentation.step(1.0, 60, true));
+}
+
+#[test]
+fn test_page_waits_for_transition() {
+    let mut presentation = Presentation::default();
+    assert!(!presentation.step(40.0, 60, false));
+    assert!(!presentation.step(1.0, 60, false));
+    assert!(presentation.step(0.0, 60, true));
+}
+
+#[test]
+fn test_restart_times_page_from_start() {
+    let mut presentation = Presentation::new(60.0);
+    presentation.step(50.0, 60, true);
+    presentation.restart();
+    assert!(!presentation.step(50.0, 60, true));
+    assert!(presentation.step(10.0, 60, true));
+}
@@ -0,0 +1,82 @@
+use notan::prelude::Color;
+use notan_sketches::emotion::analyzer::Lexicon;
+use notan_sketches::emotion::{ColorMapping, EmocatOutputDoc};
+use notan_sketches::emotion_bg_visualizer::read_along::*;
+
+const DICKINSON_DOC: &str = include_str!("../examples/assets/lb_dickinson01.json");
+
+fn dickinson() -> EmocatOutputDoc {
+    serde_json::from_str(DICKINSON_DOC).unwrap()
+}
+
+fn line(words: usize) -> ReadAlongLine {
+    ReadAlongLine {
+        number: 1,
+        words,
+        color: Color::GRAY,
+    }
+}
+
+// ===== Lines =====
+
+#[test]
+fn test_lines_are_numbered_by_meta_range() {
+    let doc = dickinson();
+    for analysis in doc.analyses.iter() {
+        let lines = read_along_lines(analysis, &Lexicon::default(), &ColorMapping::PLUTCHIK);
+        assert_eq!(lines.len(), analysis.text.split('\n').count());
+        assert_eq!(lines[0].number, analysis.meta.from_line);
+        assert_eq!(lines.last().unwrap().number, analysis.meta.to_line);
+    }
+}
+
+#[test]
+fn test_lines_are_colored_by_their_own_emotions() {
+    let doc = dickinson();
+    let lines = read_along_lines(
+        &doc.analyses[0],
+        &Lexicon::default(),
+        &ColorMapping::PLUTCHIK,
+    );
+    assert_eq!(lines[0].words, 8);
+    assert!(lines.iter().any(|line| line.color != Color::GRAY));
+}
+
+#[test]
+fn test_reading_time_follows_word_count() {
+    assert_eq!(line(0).reading_time(), 0.0);
+    assert!(line(1).reading_time() > 0.0);
+    assert_eq!(line(20).reading_time(), 20.0 * 60.0 / READ_ALONG_WPM);
+}
+
+// ===== Cursor =====
+
+#[test]
+fn test_cursor_moves_on_after_reading_time() {
+    let lines = vec![line(20), line(20), line(20)];
+    let mut cursor = ReadAlongCursor::default();
+    cursor.step(lines[0].reading_time() * 0.5, &lines, 0..3);
+    assert_eq!(cursor.line, 0);
+    cursor.step(lines[0].reading_time() * 0.6, &lines, 0..3);
+    assert_eq!(cursor.line, 1);
+}
+
+#[test]
+fn test_cursor_skips_blank_lines_and_stops_at_last_shown_line() {
+    let lines = vec![line(20), line(0), line(20), line(20)];
+    let mut cursor = ReadAlongCursor::default();
+    cursor.step(lines[0].reading_time() + 0.01, &lines, 0..3);
+    assert_eq!(cursor.line, 2);
+    cursor.step(1000.0, &lines, 0..3);
+    assert_eq!(cursor.line, 2);
+}
+
+#[test]
+fn test_cursor_jumps_to_shown_lines() {
+    let lines = vec![line(20), line(20), line(20), line(20)];
+    let mut cursor = ReadAlongCursor::default();
+    cursor.step(0.0, &lines, 2..4);
+    assert_eq!(cursor.line, 2);
+    cursor.step(0.0, &lines, 2..10);
+    assert_eq!(cursor.line, 2);
+}
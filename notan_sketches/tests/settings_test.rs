@@ -0,0 +1,99 @@
+use notan_sketches::emotion::EmocatOutputDoc;
+use notan_sketches::emotion_bg_visualizer::options::OptionValues;
+use notan_sketches::emotion_bg_visualizer::settings::*;
+use notan_sketches::emotion_bg_visualizer::visualizers::VisualizerSelection;
+use serde_json::json;
+use std::collections::HashMap;
+
+const DICKINSON_DOC: &str = include_str!("../examples/assets/lb_dickinson01.json");
+const WHITMAN_DOC: &str = include_str!("../examples/assets/lb_whitman01.json");
+
+fn defaults() -> ReaderSettings {
+    ReaderSettings {
+        version: SETTINGS_VERSION,
+        visualizer: VisualizerSelection::TiledShaders,
+        visualizer_options: HashMap::new(),
+        dynamic_text_color: false,
+        color_mapping: "Plutchik".to_string(),
+        presentation_wpm: 120.0,
+        last_read: None,
+    }
+}
+
+fn docs() -> Vec<EmocatOutputDoc> {
+    vec![
+        serde_json::from_str(DICKINSON_DOC).unwrap(),
+        serde_json::from_str(WHITMAN_DOC).unwrap(),
+    ]
+}
+
+// ===== Saved settings =====
+
+#[test]
+fn test_settings_round_trip() {
+    let mut settings = defaults();
+    settings.visualizer = VisualizerSelection::Tiles;
+    settings.dynamic_text_color = true;
+    settings.visualizer_options.insert(
+        VisualizerSelection::ColorTransition,
+        OptionValues::from_iter([
+            ("color_method".to_string(), json!("Top 3 Blend")),
+            ("transition_duration".to_string(), json!(2.5)),
+        ]),
+    );
+    settings.last_read = Some(ReadPosition::new(&docs()[1], 3));
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(ReaderSettings::from_json(&json, &defaults()), Ok(settings));
+}
+
+#[test]
+fn test_settings_missing_from_older_saves_keep_defaults() {
+    let json = r#"{"version": 1, "visualizer": "Tiles"}"#;
+    let settings = ReaderSettings::from_json(json, &defaults()).unwrap();
+    assert_eq!(settings.visualizer, VisualizerSelection::Tiles);
+    assert_eq!(settings.color_mapping, "Plutchik");
+    assert_eq!(settings.presentation_wpm, 120.0);
+}
+
+#[test]
+fn test_visualizer_options_are_kept_as_saved() {
+    let json = r#"{"visualizer_options": {"ColorTransition": {"color_method": "Grayscale"}}}"#;
+    let settings = ReaderSettings::from_json(json, &defaults()).unwrap();
+    let values = &settings.visualizer_options[&VisualizerSelection::ColorTransition];
+    assert_eq!(values["color_method"], json!("Grayscale"));
+    assert_eq!(values.get("transition_duration"), None);
+    assert_eq!(settings.version, SETTINGS_VERSION);
+}
+
+#[test]
+fn test_unknown_settings_are_ignored() {
+    let json = r#"{"version": 1, "dynamic_text_color": true, "font_size": 14}"#;
+    let settings = ReaderSettings::from_json(json, &defaults()).unwrap();
+    assert!(settings.dynamic_text_color);
+}
+
+#[test]
+fn test_settings_from_newer_version_are_rejected() {
+    let json = format!(r#"{{"version": {}}}"#, SETTINGS_VERSION + 1);
+    assert!(ReaderSettings::from_json(&json, &defaults()).is_err());
+    assert!(ReaderSettings::from_json("[]", &defaults()).is_err());
+}
+
+// ===== Read position =====
+
+#[test]
+fn test_read_position_is_found_by_title_and_author() {
+    let docs = docs();
+    let position = ReadPosition::new(&docs[1], 2);
+    assert_eq!(position.locate(&docs), Some((1, 2)));
+    assert_eq!(position.locate(&docs[1..]), Some((0, 2)));
+    assert_eq!(position.locate(&docs[..1]), None);
+}
+
+#[test]
+fn test_read_position_is_clamped_to_document() {
+    let docs = docs();
+    let paragraphs = docs[0].analyses.len();
+    let position = ReadPosition::new(&docs[0], paragraphs + 5);
+    assert_eq!(position.locate(&docs), Some((0, paragraphs)));
+}
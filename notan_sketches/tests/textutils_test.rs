@@ -0,0 +1,115 @@
+use notan::math::{vec2, Vec2};
+use notan_sketches::textutils::*;
+
+const BOUNDS: FitBounds = FitBounds {
+    max_width: 100.0,
+    max_height: 40.0,
+    min_size: 8.0,
+    max_size: 32.0,
+};
+
+/// Measures text as if every character were half as wide as the font size and lines wrapped
+/// exactly at `BOUNDS.max_width`
+fn measure(text: &str, size: f32) -> Vec2 {
+    let width = text.chars().count() as f32 * size * 0.5;
+    let lines = (width / BOUNDS.max_width).ceil().max(1.0);
+    vec2(width.min(BOUNDS.max_width), lines * size)
+}
+
+fn texts(pages: &[TextPage]) -> Vec<&str> {
+    pages.iter().map(|page| page.text.as_str()).collect()
+}
+
+fn fits(text: &str, size: f32) -> bool {
+    let measured = measure(text, size);
+    measured.x <= BOUNDS.max_width && measured.y <= BOUNDS.max_height
+}
+
+// ===== Fitting =====
+
+#[test]
+fn test_short_text_fits_at_max_size() {
+    let fit = fit_text("Hope", BOUNDS, measure);
+    assert_eq!(fit.size, BOUNDS.max_size);
+    assert_eq!(texts(&fit.pages), vec!["Hope"]);
+    assert!(fit.fits());
+}
+
+#[test]
+fn test_fit_is_the_largest_size_that_fits() {
+    let text = "Hope is the thing with feathers";
+    let fit = fit_text(text, BOUNDS, measure);
+    assert!(fit.fits());
+    assert!(fit.size < BOUNDS.max_size);
+    assert!(fits(text, fit.size));
+    assert!(!fits(text, fit.size + 0.5));
+}
+
+#[test]
+fn test_fit_size_stops_at_min_size() {
+    assert_eq!(fit_size(8.0, 32.0, |size| size <= 8.0), Some(8.0));
+    assert_eq!(fit_size(8.0, 32.0, |_| false), None);
+}
+
+#[test]
+fn test_fit_size_measures_few_sizes() {
+    let mut measured = 0;
+    fit_size(8.0, 32.0, |size| {
+        measured += 1;
+        size <= 17.3
+    });
+    // Stepping down 2 points at a time would take 8 measurements
+    assert!(measured <= 8, "measured {} sizes", measured);
+}
+
+// ===== Pagination =====
+
+#[test]
+fn test_text_too_long_for_min_size_is_paginated() {
+    let text = "Hope is the thing with feathers\nThat perches in the soul,\n\
+                And sings the tune without the words,\nAnd never stops at all,\n\
+                And sweetest in the gale is heard;\nAnd sore must be the storm\n\
+                That could abash the little bird\nThat kept so many warm.";
+    let fit = fit_text(text, BOUNDS, measure);
+    assert!(!fit.fits());
+    assert_eq!(fit.size, BOUNDS.min_size);
+    for page in fit.pages.iter() {
+        assert!(
+            fits(&page.text, BOUNDS.min_size),
+            "page does not fit: {:?}",
+            page
+        );
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let paged_words: Vec<&str> = fit
+        .pages
+        .iter()
+        .flat_map(|page| page.text.split_whitespace())
+        .collect();
+    assert_eq!(paged_words, words);
+}
+
+#[test]
+fn test_pages_are_filled_and_keep_line_breaks() {
+    let pages = paginate("one two\nthree four five", |page| page.len() <= 14);
+    assert_eq!(texts(&pages), vec!["one two\nthree", "four five"]);
+}
+
+#[test]
+fn test_word_too_long_for_a_page_gets_its_own_page() {
+    let pages = paginate("a incomprehensibilities b", |page| page.len() <= 5);
+    assert_eq!(texts(&pages), vec!["a", "incomprehensibilities", "b"]);
+}
+
+#[test]
+fn test_pages_know_which_lines_they_are_on() {
+    let pages = paginate("one two\n\nthree four\nfive", |page| page.len() <= 9);
+    assert_eq!(texts(&pages), vec!["one two", "three", "four\nfive"]);
+    let lines: Vec<_> = pages.iter().map(|page| page.lines()).collect();
+    assert_eq!(lines, vec![0..1, 2..3, 2..4]);
+}
+
+#[test]
+fn test_paginating_blank_text_gives_no_pages() {
+    assert!(paginate(" \n ", |_| true).is_empty());
+}
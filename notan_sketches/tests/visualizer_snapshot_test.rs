@@ -0,0 +1,111 @@
+use notan::prelude::Color;
+use notan_sketches::emotion::EmocatOutputDoc;
+use notan_sketches::emotion_bg_visualizer::harness::{assert_snapshot, hex, run_headless};
+use notan_sketches::emotion_bg_visualizer::visualizers::color_transition::ColorTransitionVisualizer;
+use notan_sketches::emotion_bg_visualizer::visualizers::tile::TilesVisualizer;
+use notan_sketches::emotion_bg_visualizer::visualizers::tiled_shaders::TiledShadersVisualizer;
+use notan_sketches::emotion_bg_visualizer::visualizers::EmoVisualizer;
+
+const DICKINSON_DOC: &str = include_str!("../examples/assets/lb_dickinson01.json");
+const SEED: u64 = 42;
+const FRAMES: usize = 200;
+
+fn dickinson() -> EmocatOutputDoc {
+    serde_json::from_str(DICKINSON_DOC).unwrap()
+}
+
+fn visualizers() -> Vec<(&'static str, Box<dyn EmoVisualizer>)> {
+    vec![
+        (
+            "color_transition",
+            Box::new(ColorTransitionVisualizer::new(
+                Color::WHITE,
+                Color::BLACK,
+                false,
+            )),
+        ),
+        (
+            "tiles",
+            Box::new(TilesVisualizer::new_headless(
+                Color::WHITE,
+                Color::BLACK,
+                false,
+            )),
+        ),
+        (
+            "tiled_shaders",
+            Box::new(TiledShadersVisualizer::new_headless(
+                Color::WHITE,
+                Color::BLACK,
+                false,
+            )),
+        ),
+    ]
+}
+
+// ===== Snapshots =====
+
+/// Snapshots are kept in tests/snapshots. Rerun with UPDATE_SNAPSHOTS=1 to record them after
+/// intended changes to the visualizers, and commit them along with the changes.
+#[test]
+fn test_visualizer_snapshots() {
+    let doc = dickinson();
+    for (name, mut visualizer) in visualizers() {
+        for (i, analysis) in doc.analyses.iter().enumerate() {
+            let snapshot = run_headless(visualizer.as_mut(), analysis, SEED, FRAMES);
+            assert_snapshot(
+                format!(
+                    "{}/tests/snapshots/{}_p{}.json",
+                    env!("CARGO_MANIFEST_DIR"),
+                    name,
+                    i + 1
+                ),
+                &snapshot,
+            );
+        }
+    }
+}
+
+// ===== Determinism =====
+
+#[test]
+fn test_same_seed_gives_same_snapshot() {
+    let doc = dickinson();
+    for ((_, mut first), (_, mut second)) in visualizers().into_iter().zip(visualizers()) {
+        let a = run_headless(first.as_mut(), &doc.analyses[0], SEED, FRAMES);
+        let b = run_headless(second.as_mut(), &doc.analyses[0], SEED, FRAMES);
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn test_rerunning_a_visualizer_gives_same_snapshot() {
+    let doc = dickinson();
+    for (name, mut visualizer) in visualizers() {
+        let a = run_headless(visualizer.as_mut(), &doc.analyses[1], SEED, FRAMES);
+        let b = run_headless(visualizer.as_mut(), &doc.analyses[1], SEED, FRAMES);
+        assert_eq!(a, b, "{} depends on state from a previous run", name);
+    }
+}
+
+#[test]
+fn test_tiles_fill_the_work_area() {
+    let doc = dickinson();
+    let mut visualizer = TilesVisualizer::new_headless(Color::WHITE, Color::BLACK, false);
+    let snapshot = run_headless(&mut visualizer, &doc.analyses[0], SEED, 1);
+    assert!(!snapshot.tiles.is_empty());
+    let cols = snapshot.tiles[0].len() as f32;
+    assert!(cols * snapshot.tile_size[0] >= 1920.0 - 0.01);
+    assert!(snapshot.tiles.len() as f32 * snapshot.tile_size[1] >= 1080.0 - 0.01);
+}
+
+#[test]
+fn test_color_transition_reaches_target() {
+    let doc = dickinson();
+    let mut visualizer = ColorTransitionVisualizer::new(Color::WHITE, Color::BLACK, false);
+    // Longer than the default transition duration at 60 fps
+    let snapshot = run_headless(&mut visualizer, &doc.analyses[0], SEED, 600);
+    assert_ne!(snapshot.target_color, hex(Color::WHITE));
+    assert_eq!(snapshot.bg_color, snapshot.target_color);
+    assert!(visualizer.transition.is_complete());
+}